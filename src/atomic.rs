@@ -0,0 +1,291 @@
+//! ファイルの安全な書き換え
+//!
+//! ノートを直接上書きすると、書き込みの途中でプロセスが終了したりディスクが一杯に
+//! なったりした場合に元の内容が失われる。同じディレクトリの一時ファイルに書き込んで
+//! `fsync` し、元のファイルへリネームすることで、常に変更前か変更後の内容が残るようにする。
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use tempfile::Builder;
+
+/// ファイルの内容を安全に置き換える
+///
+/// `fs::write` と同様に使う。ファイルが既に存在する場合はパーミッションを引き継ぐ。
+/// シンボリックリンクの場合はリンク先を置き換え、ハードリンクされたファイルは
+/// リンクを保つためにその場で書き換える。
+/// ハードリンクの場合を除き、失敗しても元のファイルは変更されず、一時ファイルも残らない。
+///
+/// # 引数
+///
+/// * `path` - 書き込むファイルのパス（親ディレクトリは存在している必要がある）
+/// * `contents` - 書き込む内容
+///
+/// # Errors
+///
+/// 一時ファイルの作成・書き込み・同期・リネームのいずれかに失敗した場合
+pub(crate) fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    write_with(path, |file| file.write_all(contents.as_ref()))
+}
+
+/// 一時ファイルへの書き込み処理を指定してファイルを置き換える
+fn write_with(path: &Path, write: impl FnOnce(&mut File) -> io::Result<()>) -> io::Result<()> {
+    // シンボリックリンクはリンクではなくリンク先を置き換える
+    let target = resolve_target(path)?;
+    let path = target.as_path();
+    let dir = parent_dir(path);
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => Some(metadata),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+
+    // ハードリンクはリネームで切れてしまうため、その場で書き換える
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.is_file() && metadata.nlink() > 1)
+        {
+            return write_in_place(path, write);
+        }
+    }
+    let permissions = metadata.map(|metadata| metadata.permissions());
+
+    // Obsidianや検索の対象にならないよう、隠しファイルとして作成する
+    let mut builder = Builder::new();
+    builder.prefix(".thn-").suffix(".tmp");
+    #[cfg(unix)]
+    {
+        // 新規ファイルは通常のファイルと同じくumaskに従ったパーミッションにする
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(fs::Permissions::from_mode(0o666));
+    }
+    let mut temp = builder.tempfile_in(dir)?;
+
+    write(temp.as_file_mut())?;
+    if let Some(permissions) = permissions {
+        temp.as_file().set_permissions(permissions)?;
+    }
+    temp.as_file().sync_all()?;
+    temp.persist(path).map_err(|err| err.error)?;
+
+    sync_dir(dir);
+    Ok(())
+}
+
+/// 書き込み先の実際のファイルのパスを返す
+///
+/// シンボリックリンクはリンク先をたどる。リンク先がまだ存在しない場合もリンク先を返す。
+fn resolve_target(path: &Path) -> io::Result<PathBuf> {
+    match fs::canonicalize(path) {
+        Ok(target) => Ok(target),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => match fs::read_link(path) {
+            Ok(link) => Ok(parent_dir(path).join(link)),
+            Err(_) => Ok(path.to_path_buf()),
+        },
+        Err(err) => Err(err),
+    }
+}
+
+/// ファイルの親ディレクトリを返す（相対パスのファイル名のみの場合はカレントディレクトリ）
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// ファイルをその場で書き換える
+///
+/// ハードリンクされたファイル用。一時ファイルからのリネームと異なり、書き込み中に
+/// 失敗すると途中までの内容が残る。
+#[cfg(unix)]
+fn write_in_place(path: &Path, write: impl FnOnce(&mut File) -> io::Result<()>) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(path)?;
+    write(&mut file)?;
+    file.sync_all()
+}
+
+/// リネームを確実に記録するため、ディレクトリを同期する
+///
+/// ファイルの置き換えは完了しているため、失敗しても無視する。
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// ディレクトリ内のファイル名の一覧を返すヘルパー関数
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_write_replaces_content() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("2026-01-15.md");
+        fs::write(&path, "- 09:00 古い内容\n").unwrap();
+
+        write(&path, "- 09:00 古い内容\n- 10:00 新しい内容\n").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "- 09:00 古い内容\n- 10:00 新しい内容\n"
+        );
+        assert_eq!(file_names(dir.path()), vec!["2026-01-15.md"]);
+    }
+
+    #[test]
+    fn test_write_creates_new_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("new.md");
+
+        write(&path, "内容\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "内容\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_preserves_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("note.md");
+        fs::write(&path, "内容\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        write(&path, "新しい内容\n").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    #[test]
+    fn test_failed_write_keeps_original() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("2026-01-15.md");
+        fs::write(&path, "- 09:00 今日のメモ\n").unwrap();
+
+        // 途中まで書き込んだところでディスクが一杯になった場合
+        let result = write_with(&path, |file| {
+            file.write_all(b"- 09:00")?;
+            Err(io::Error::new(io::ErrorKind::StorageFull, "no space left"))
+        });
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::StorageFull);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "- 09:00 今日のメモ\n");
+        assert_eq!(file_names(dir.path()), vec!["2026-01-15.md"]);
+    }
+
+    #[test]
+    fn test_failed_write_does_not_create_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("new.md");
+
+        let result = write_with(&path, |_| Err(io::Error::other("interrupted")));
+
+        assert!(result.is_err());
+        assert!(file_names(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_failed_rename_leaves_no_temp_file() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("2026-01-15.md"), "- 09:00 今日のメモ\n").unwrap();
+        // 置き換え先がディレクトリのためリネームに失敗する
+        let path = dir.path().join("note.md");
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("keep.md"), "内容\n").unwrap();
+
+        assert!(write(&path, "新しい内容\n").is_err());
+
+        assert_eq!(fs::read_to_string(path.join("keep.md")).unwrap(), "内容\n");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("2026-01-15.md")).unwrap(),
+            "- 09:00 今日のメモ\n"
+        );
+        assert_eq!(file_names(dir.path()), vec!["2026-01-15.md", "note.md"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_through_symlink() {
+        let dir = tempdir().unwrap();
+        let shared = dir.path().join("shared");
+        fs::create_dir(&shared).unwrap();
+        let target = shared.join("2026-01-15.md");
+        fs::write(&target, "- 09:00 外部のノート\n").unwrap();
+        let link = dir.path().join("2026-01-15.md");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write(&link, "- 09:00 外部のノート\n- 10:00 追記\n").unwrap();
+
+        // リンクはそのまま残り、リンク先が書き換わる
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_link(&link).unwrap(), target);
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            "- 09:00 外部のノート\n- 10:00 追記\n"
+        );
+        assert_eq!(file_names(&shared), vec!["2026-01-15.md"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_through_dangling_symlink() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("target.md");
+        let link = dir.path().join("link.md");
+        std::os::unix::fs::symlink("target.md", &link).unwrap();
+
+        write(&link, "内容\n").unwrap();
+
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_to_string(&target).unwrap(), "内容\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_keeps_hard_link() {
+        let dir = tempdir().unwrap();
+        let original = dir.path().join("original.md");
+        let linked = dir.path().join("linked.md");
+        fs::write(&original, "内容\n").unwrap();
+        fs::hard_link(&original, &linked).unwrap();
+
+        write(&linked, "新しい内容\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&original).unwrap(), "新しい内容\n");
+        assert_eq!(fs::read_to_string(&linked).unwrap(), "新しい内容\n");
+    }
+}
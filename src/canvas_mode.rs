@@ -0,0 +1,304 @@
+//! CANVASモードのメモ保存
+//!
+//! ThinoのCANVASモードと同様に、Obsidianのキャンバスファイル（`.canvas`）に
+//! メモをテキストノードとして追加する。既存のノードとエッジはそのまま保持する。
+
+use std::fs;
+use std::hash::{BuildHasher, RandomState};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::atomic;
+use crate::lock;
+use crate::memo::MemoError;
+
+/// 追加するノードの幅
+const NODE_WIDTH: i64 = 250;
+
+/// 追加するノードの高さ（1行あたり）
+const NODE_LINE_HEIGHT: i64 = 24;
+
+/// 追加するノードの高さの余白
+const NODE_PADDING: i64 = 36;
+
+/// 既存ノードとの間隔
+const NODE_GAP: i64 = 20;
+
+/// キャンバスファイルの内容
+///
+/// 未知のキーは `extra` に保持し、書き戻し時にそのまま出力する。
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Canvas {
+    #[serde(default)]
+    nodes: Vec<Value>,
+
+    #[serde(default)]
+    edges: Vec<Value>,
+
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+/// キャンバスファイルのパスを生成する
+///
+/// 拡張子が省略されている場合は `.canvas` を付与する。
+///
+/// # 引数
+///
+/// * `vault_path` - Obsidian Vaultのパス
+/// * `file` - メモを保存するキャンバスファイル（Vaultルートからの相対パス）
+pub fn canvas_file_path(vault_path: &Path, file: &str) -> PathBuf {
+    let path = vault_path.join(file);
+    if path.extension().is_some() {
+        path
+    } else {
+        path.with_extension("canvas")
+    }
+}
+
+/// キャンバスファイルにテキストノードを追加する
+///
+/// ファイルが存在しない場合は新規作成する。新しいノードは既存ノードと重ならないよう、
+/// 全ノードの下端より下に配置する。
+///
+/// # 引数
+///
+/// * `path` - キャンバスファイルのパス
+/// * `text` - ノードのテキスト
+///
+/// # Errors
+///
+/// - `MemoError::Io` - ファイル操作に失敗した場合
+/// - `MemoError::InvalidCanvas` - キャンバスファイルのパースに失敗した場合
+/// - `MemoError::WriteFailed` - ファイル書き込みに失敗した場合
+pub fn append_text_node(path: &Path, text: &str) -> Result<(), MemoError> {
+    let _lock = lock::lock(path)?;
+
+    let mut canvas = if path.exists() {
+        let content = fs::read_to_string(path)?;
+        parse_canvas(&content).map_err(|err| MemoError::InvalidCanvas(path.to_path_buf(), err))?
+    } else {
+        Canvas::default()
+    };
+
+    let node = new_text_node(&canvas, text);
+    canvas.nodes.push(node);
+
+    atomic::write(path, to_canvas_json(&canvas))
+        .map_err(|_| MemoError::WriteFailed(path.to_path_buf()))?;
+
+    Ok(())
+}
+
+/// キャンバスファイルの内容をパースする
+///
+/// 空のファイルは空のキャンバスとして扱う。
+fn parse_canvas(content: &str) -> Result<Canvas, serde_json::Error> {
+    if content.trim().is_empty() {
+        return Ok(Canvas::default());
+    }
+    serde_json::from_str(content)
+}
+
+/// キャンバスをObsidianと同じ形式（タブインデント）のJSONに変換する
+fn to_canvas_json(canvas: &Canvas) -> String {
+    let mut buf = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"\t");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+    canvas
+        .serialize(&mut serializer)
+        .expect("canvas values are always serializable");
+    String::from_utf8(buf).expect("serde_json always produces valid UTF-8")
+}
+
+/// 追加するテキストノードを生成する
+fn new_text_node(canvas: &Canvas, text: &str) -> Value {
+    let (x, y) = next_position(&canvas.nodes);
+    let lines = i64::try_from(text.lines().count().max(1)).unwrap_or(i64::MAX);
+
+    json!({
+        "id": unique_id(&canvas.nodes),
+        "type": "text",
+        "text": text,
+        "x": x,
+        "y": y,
+        "width": NODE_WIDTH,
+        "height": NODE_PADDING + NODE_LINE_HEIGHT.saturating_mul(lines),
+    })
+}
+
+/// 新しいノードの位置を返す
+///
+/// 既存ノードの左端に揃え、全ノードの下端から `NODE_GAP` 空けた位置。
+/// ノードがなければ原点。
+fn next_position(nodes: &[Value]) -> (i64, i64) {
+    let number = |node: &Value, key: &str| node.get(key).and_then(Value::as_f64);
+
+    let left = nodes
+        .iter()
+        .filter_map(|node| number(node, "x"))
+        .reduce(f64::min);
+    let bottom = nodes
+        .iter()
+        .filter_map(|node| Some(number(node, "y")? + number(node, "height").unwrap_or(0.0)))
+        .reduce(f64::max);
+
+    match (left, bottom) {
+        (Some(left), Some(bottom)) => (left.floor() as i64, bottom.ceil() as i64 + NODE_GAP),
+        _ => (0, 0),
+    }
+}
+
+/// 既存ノードと重複しないIDを生成する
+///
+/// Obsidianと同じく16桁の16進数文字列。
+fn unique_id(nodes: &[Value]) -> String {
+    loop {
+        let id = format!("{:016x}", RandomState::new().hash_one(nodes.len()));
+        let exists = nodes
+            .iter()
+            .any(|node| node.get("id").and_then(Value::as_str) == Some(id.as_str()));
+        if !exists {
+            return id;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const EXISTING_CANVAS: &str = r#"{
+	"nodes":[
+		{"id":"aaaaaaaaaaaaaaaa","x":-100,"y":0,"width":250,"height":60,"type":"text","text":"a","color":"1"},
+		{"id":"bbbbbbbbbbbbbbbb","x":200,"y":100,"width":400,"height":400,"type":"file","file":"note.md"}
+	],
+	"edges":[
+		{"id":"cccccccccccccccc","fromNode":"aaaaaaaaaaaaaaaa","fromSide":"right","toNode":"bbbbbbbbbbbbbbbb","toSide":"left"}
+	]
+}"#;
+
+    #[test]
+    fn test_canvas_file_path_adds_extension() {
+        let result = canvas_file_path(Path::new("/vault"), "Thino");
+        assert_eq!(result, PathBuf::from("/vault/Thino.canvas"));
+    }
+
+    #[test]
+    fn test_canvas_file_path_keeps_extension() {
+        let result = canvas_file_path(Path::new("/vault"), "Boards/memos.canvas");
+        assert_eq!(result, PathBuf::from("/vault/Boards/memos.canvas"));
+    }
+
+    #[test]
+    fn test_append_text_node_creates_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Boards").join("Thino.canvas");
+
+        append_text_node(&path, "- 12:00 メモ").unwrap();
+
+        let canvas: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let nodes = canvas["nodes"].as_array().unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0]["type"], "text");
+        assert_eq!(nodes[0]["text"], "- 12:00 メモ");
+        assert_eq!(nodes[0]["x"], 0);
+        assert_eq!(nodes[0]["y"], 0);
+        assert_eq!(nodes[0]["id"].as_str().unwrap().len(), 16);
+        assert_eq!(canvas["edges"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_append_text_node_preserves_existing() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Thino.canvas");
+        fs::write(&path, EXISTING_CANVAS).unwrap();
+
+        append_text_node(&path, "メモ").unwrap();
+
+        let before: Value = serde_json::from_str(EXISTING_CANVAS).unwrap();
+        let after: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let nodes = after["nodes"].as_array().unwrap();
+
+        // 既存のノードとエッジは変更されない
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[..2], before["nodes"].as_array().unwrap()[..]);
+        assert_eq!(after["edges"], before["edges"]);
+
+        // 新しいノードは既存ノードの下に配置される
+        assert_eq!(nodes[2]["x"], -100);
+        assert_eq!(nodes[2]["y"], 520);
+    }
+
+    #[test]
+    fn test_append_text_node_preserves_key_order() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Thino.canvas");
+        fs::write(&path, EXISTING_CANVAS).unwrap();
+
+        append_text_node(&path, "メモ").unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let id = content.find("\"id\": \"aaaaaaaaaaaaaaaa\"").unwrap();
+        let x = content.find("\"x\": -100").unwrap();
+        let color = content.find("\"color\": \"1\"").unwrap();
+        assert!(id < x && x < color);
+    }
+
+    #[test]
+    fn test_append_text_node_keeps_unknown_keys() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Thino.canvas");
+        fs::write(&path, r#"{"nodes":[],"edges":[],"metadata":{"v":1}}"#).unwrap();
+
+        append_text_node(&path, "メモ").unwrap();
+
+        let after: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(after["metadata"]["v"], 1);
+    }
+
+    #[test]
+    fn test_append_text_node_empty_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Thino.canvas");
+        fs::write(&path, "").unwrap();
+
+        append_text_node(&path, "メモ").unwrap();
+
+        let after: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(after["nodes"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_append_text_node_invalid_canvas() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Thino.canvas");
+        fs::write(&path, "not json").unwrap();
+
+        let result = append_text_node(&path, "メモ");
+
+        assert!(matches!(result, Err(MemoError::InvalidCanvas(..))));
+        // 不正なファイルは上書きしない
+        assert_eq!(fs::read_to_string(&path).unwrap(), "not json");
+    }
+
+    #[test]
+    fn test_new_text_node_height_grows_with_lines() {
+        let canvas = Canvas::default();
+        let single = new_text_node(&canvas, "a");
+        let multi = new_text_node(&canvas, "a\nb\nc");
+        assert_eq!(single["height"], 60);
+        assert_eq!(multi["height"], 108);
+    }
+
+    #[test]
+    fn test_unique_id_differs_from_existing() {
+        let nodes = vec![json!({"id": "aaaaaaaaaaaaaaaa"})];
+        let id = unique_id(&nodes);
+        assert_ne!(id, "aaaaaaaaaaaaaaaa");
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
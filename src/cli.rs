@@ -2,27 +2,463 @@
 //!
 //! clapのderiveマクロを使用してCLI引数を定義する。
 
+use std::env;
+use std::ffi::OsString;
 use std::path::PathBuf;
 
-use clap::Parser;
+use chrono::{Days, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+
+/// サブコマンド省略時に補うサブコマンド
+const DEFAULT_SUBCOMMAND: &str = "add";
+
+/// サブコマンドの前に指定できるオプション
+const TOP_LEVEL_FLAGS: [&str; 8] = [
+    "-h",
+    "--help",
+    "-V",
+    "--version",
+    "-i",
+    "--init",
+    "-c",
+    "--config",
+];
+
+/// 値を取り、サブコマンドの前後どちらにも指定できるオプション
+const GLOBAL_OPTIONS_WITH_VALUE: [&str; 2] = ["--tz", "--vault"];
 
 /// Obsidianデイリーノートにメモを追記するCLIツール（Thino互換）
 #[derive(Parser)]
 #[command(
     name = "thn",
     version,
-    about = "CLI tool for appending memos to Obsidian daily notes (Thino compatible)"
+    about = "CLI tool for appending memos to Obsidian daily notes (Thino compatible)",
+    override_usage = "thn [OPTIONS] [MEMO]...\n       thn <COMMAND>",
+    args_conflicts_with_subcommands = true
 )]
 pub struct Cli {
-    /// Vaultパスを設定（省略時は対話形式）
-    #[arg(short = 'i', long, value_name = "PATH")]
+    /// サブコマンド
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Vaultパスを設定（`thn init` の旧形式）
+    #[arg(short = 'i', long, value_name = "PATH", hide = true)]
     pub init: Option<Option<PathBuf>>,
 
-    /// 現在の設定を表示
-    #[arg(short = 'c', long)]
+    /// 現在の設定を表示（`thn config` の旧形式）
+    #[arg(short = 'c', long, hide = true)]
     pub config: bool,
 
-    /// メモ内容（複数引数はスペースで結合）
-    #[arg(trailing_var_arg = true)]
+    /// 記録日時に使うタイムゾーン（IANA名、例: Asia/Tokyo。設定ファイルの timezone より優先）
+    #[arg(long, value_name = "TZ", global = true)]
+    pub tz: Option<Tz>,
+
+    /// 使用する名前付きVault（設定ファイルの [vaults.<NAME>]、環境変数 THN_VAULT でも指定可）
+    #[arg(long, value_name = "NAME", global = true)]
+    pub vault: Option<String>,
+}
+
+/// サブコマンド
+#[derive(Subcommand)]
+pub enum Command {
+    /// Vaultパスを設定（省略時は対話形式）
+    Init(InitArgs),
+
+    /// 現在の設定を表示
+    Config,
+
+    /// メモを追記（サブコマンド省略時の既定）
+    Add(AddArgs),
+
+    /// デイリーノートのメモを表示（省略時は今日）
+    List(ListArgs),
+
+    /// デイリーノートのメモを検索
+    Search(SearchArgs),
+}
+
+/// `init` サブコマンドの引数
+#[derive(Args)]
+pub struct InitArgs {
+    /// 名前付きVaultとして登録する（省略時は名前なしのVault）
+    #[arg(short, long, value_name = "NAME")]
+    pub name: Option<String>,
+
+    /// Obsidian Vaultのパス
+    pub path: Option<PathBuf>,
+}
+
+/// `add` サブコマンドの引数
+#[derive(Args, Default)]
+pub struct AddArgs {
+    /// エディタ（$VISUAL / $EDITOR）でメモを作成
+    #[arg(short = 'e', long)]
+    pub edit: bool,
+
+    /// 記録時刻（HH:MM または HH:MM:SS、省略時は現在時刻）
+    #[arg(long, value_name = "TIME", value_parser = parse_time)]
+    pub at: Option<NaiveTime>,
+
+    /// 記録日（YYYY-MM-DD、today、yesterday、tomorrow、-N / +N 日、省略時は今日）
+    #[arg(
+        short,
+        long,
+        value_name = "DATE",
+        value_parser = parse_date_arg,
+        allow_hyphen_values = true
+    )]
+    pub date: Option<DateArg>,
+
+    /// メモ内容（複数引数はスペースで結合、`-` 指定時やパイプ入力時は標準入力から読み込む）
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     pub memo: Vec<String>,
 }
+
+/// `add --date` で指定する記録日
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateArg {
+    /// 日付の指定
+    Date(NaiveDate),
+
+    /// 今日からの相対日数
+    Relative(i64),
+}
+
+impl DateArg {
+    /// 今日の日付を基準に記録日を求める
+    ///
+    /// 日付の範囲外になる場合は `None`。
+    pub fn resolve(self, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            DateArg::Date(date) => Some(date),
+            DateArg::Relative(days) if days < 0 => {
+                today.checked_sub_days(Days::new(days.unsigned_abs()))
+            }
+            DateArg::Relative(days) => today.checked_add_days(Days::new(days.unsigned_abs())),
+        }
+    }
+}
+
+/// `--date` の値を解析する
+fn parse_date_arg(value: &str) -> Result<DateArg, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "today" => return Ok(DateArg::Relative(0)),
+        "yesterday" => return Ok(DateArg::Relative(-1)),
+        "tomorrow" => return Ok(DateArg::Relative(1)),
+        _ => {}
+    }
+
+    if value.starts_with(['-', '+']) {
+        return value
+            .parse()
+            .map(DateArg::Relative)
+            .map_err(|_| format!("invalid day offset: {value}"));
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(DateArg::Date)
+        .map_err(|_| format!("invalid date: {value} (expected YYYY-MM-DD, today, yesterday or -N)"))
+}
+
+/// `--at` の値（`HH:MM` または `HH:MM:SS`）を解析する
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+        .map_err(|_| format!("invalid time: {value} (expected HH:MM or HH:MM:SS)"))
+}
+
+/// `list` サブコマンドの引数
+#[derive(Args)]
+pub struct ListArgs {
+    /// 表示する日付（YYYY-MM-DD）
+    #[arg(short, long, value_name = "DATE", conflicts_with_all = ["from", "to"])]
+    pub date: Option<NaiveDate>,
+
+    /// 期間の開始日（YYYY-MM-DD、省略時は --to と同じ日）
+    #[arg(long, value_name = "DATE")]
+    pub from: Option<NaiveDate>,
+
+    /// 期間の終了日（YYYY-MM-DD、省略時は今日）
+    #[arg(long, value_name = "DATE")]
+    pub to: Option<NaiveDate>,
+
+    /// JSON形式で出力
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `search` サブコマンドの引数
+#[derive(Args)]
+pub struct SearchArgs {
+    /// 検索文字列（--tag 指定時は省略可）
+    #[arg(required_unless_present = "tag")]
+    pub query: Option<String>,
+
+    /// 検索文字列を正規表現として扱う
+    #[arg(short, long)]
+    pub regex: bool,
+
+    /// 大文字小文字を区別しない
+    #[arg(short, long)]
+    pub ignore_case: bool,
+
+    /// タグで絞り込む（複数指定時はすべて含むメモのみ）
+    #[arg(short, long, value_name = "TAG")]
+    pub tag: Vec<String>,
+
+    /// 期間の開始日（YYYY-MM-DD）
+    #[arg(long, value_name = "DATE")]
+    pub from: Option<NaiveDate>,
+
+    /// 期間の終了日（YYYY-MM-DD）
+    #[arg(long, value_name = "DATE")]
+    pub to: Option<NaiveDate>,
+
+    /// JSON形式で出力
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// コマンドライン引数を解析する
+///
+/// 最初の引数がサブコマンドでもトップレベルのオプションでもない場合は `add` を補い、
+/// `thn <memo>` を `thn add <memo>` として扱う。
+pub fn parse() -> Cli {
+    Cli::parse_from(with_default_subcommand(env::args_os().collect()))
+}
+
+/// 必要に応じて既定のサブコマンドを補った引数を返す
+///
+/// サブコマンド名と同じ語で始まるメモは `thn add <memo>` または `thn -- <memo>` で追記する。
+/// サブコマンドより前の `--tz <TZ>` や `--vault <NAME>` はサブコマンドの直後に移す。
+fn with_default_subcommand(mut args: Vec<OsString>) -> Vec<OsString> {
+    let global_end = leading_global_options_end(&args);
+    let globals: Vec<OsString> = args.drain(1.min(args.len())..global_end).collect();
+
+    let first = args
+        .get(1)
+        .map(|first| first.to_string_lossy().into_owned());
+    let top_level = first
+        .as_deref()
+        .is_some_and(|first| TOP_LEVEL_FLAGS.contains(&first) || first.starts_with("--init="));
+    let subcommand = first.as_deref().is_some_and(is_subcommand);
+
+    if top_level {
+        args.splice(1..1, globals);
+    } else {
+        if !subcommand {
+            args.insert(args.len().min(1), DEFAULT_SUBCOMMAND.into());
+        }
+        args.splice(2..2, globals);
+    }
+    args
+}
+
+/// 先頭に並ぶ値付きのグローバルオプション（`--tz <TZ>`、`--vault <NAME>`）の終端を返す
+fn leading_global_options_end(args: &[OsString]) -> usize {
+    let mut index = 1;
+    while let Some(arg) = args.get(index).map(|arg| arg.to_string_lossy()) {
+        if GLOBAL_OPTIONS_WITH_VALUE.contains(&arg.as_ref()) {
+            index += 2;
+        } else if GLOBAL_OPTIONS_WITH_VALUE
+            .iter()
+            .any(|option| arg.starts_with(&format!("{option}=")))
+        {
+            index += 1;
+        } else {
+            break;
+        }
+    }
+    index.min(args.len())
+}
+
+/// サブコマンド名（clapが自動で追加する `help` を含む）かどうかを判定
+fn is_subcommand(name: &str) -> bool {
+    name == "help"
+        || Cli::command()
+            .get_subcommands()
+            .any(|command| command.get_name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Cli {
+        let args = args.iter().map(OsString::from).collect();
+        Cli::try_parse_from(with_default_subcommand(args)).unwrap()
+    }
+
+    fn memo_of(cli: Cli) -> Vec<String> {
+        match cli.command {
+            Some(Command::Add(args)) => args.memo,
+            _ => panic!("not an add command"),
+        }
+    }
+
+    #[test]
+    fn test_memo_without_subcommand() {
+        let cli = parse_args(&["thn", "hello", "world"]);
+        assert_eq!(memo_of(cli), vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn test_no_arguments_is_add() {
+        let cli = parse_args(&["thn"]);
+        assert!(memo_of(cli).is_empty());
+    }
+
+    #[test]
+    fn test_memo_starting_with_hyphen() {
+        let cli = parse_args(&["thn", "-5", "degrees", "--outside"]);
+        assert_eq!(memo_of(cli), vec!["-5", "degrees", "--outside"]);
+    }
+
+    #[test]
+    fn test_stdin_dash_is_add() {
+        let cli = parse_args(&["thn", "-"]);
+        assert_eq!(memo_of(cli), vec!["-"]);
+    }
+
+    #[test]
+    fn test_edit_flag_without_subcommand() {
+        let cli = parse_args(&["thn", "--edit", "draft"]);
+        match cli.command {
+            Some(Command::Add(args)) => {
+                assert!(args.edit);
+                assert_eq!(args.memo, vec!["draft"]);
+            }
+            _ => panic!("not an add command"),
+        }
+    }
+
+    #[test]
+    fn test_subcommand_name_takes_precedence() {
+        let cli = parse_args(&["thn", "list"]);
+        assert!(matches!(cli.command, Some(Command::List(_))));
+    }
+
+    #[test]
+    fn test_double_dash_forces_memo() {
+        let cli = parse_args(&["thn", "--", "list", "groceries"]);
+        assert_eq!(memo_of(cli), vec!["list", "groceries"]);
+    }
+
+    #[test]
+    fn test_explicit_add_with_subcommand_name() {
+        let cli = parse_args(&["thn", "add", "config", "changes"]);
+        assert_eq!(memo_of(cli), vec!["config", "changes"]);
+    }
+
+    #[test]
+    fn test_legacy_flags() {
+        let cli = parse_args(&["thn", "--init", "/vault"]);
+        assert_eq!(cli.init, Some(Some(PathBuf::from("/vault"))));
+        assert!(cli.command.is_none());
+
+        let cli = parse_args(&["thn", "-c"]);
+        assert!(cli.config);
+    }
+
+    #[test]
+    fn test_init_subcommand() {
+        let cli = parse_args(&["thn", "init", "/vault"]);
+        match cli.command {
+            Some(Command::Init(args)) => assert_eq!(args.path, Some(PathBuf::from("/vault"))),
+            _ => panic!("not an init command"),
+        }
+
+        let cli = parse_args(&["thn", "init", "--name", "work", "/vault"]);
+        match cli.command {
+            Some(Command::Init(args)) => {
+                assert_eq!(args.name.as_deref(), Some("work"));
+                assert_eq!(args.path, Some(PathBuf::from("/vault")));
+            }
+            _ => panic!("not an init command"),
+        }
+    }
+
+    #[test]
+    fn test_add_with_timestamp() {
+        let cli = parse_args(&["thn", "--at", "9:15", "--date", "-2", "会議", "メモ"]);
+        match cli.command {
+            Some(Command::Add(args)) => {
+                assert_eq!(args.at, NaiveTime::from_hms_opt(9, 15, 0));
+                assert_eq!(args.date, Some(DateArg::Relative(-2)));
+                assert_eq!(args.memo, vec!["会議", "メモ"]);
+            }
+            _ => panic!("not an add command"),
+        }
+    }
+
+    #[test]
+    fn test_timezone_before_subcommand() {
+        let cli = parse_args(&["thn", "--tz", "Asia/Tokyo", "list"]);
+        assert_eq!(cli.tz, Some(Tz::Asia__Tokyo));
+        assert!(matches!(cli.command, Some(Command::List(_))));
+
+        let cli = parse_args(&["thn", "--tz=UTC", "会議", "メモ"]);
+        assert_eq!(cli.tz, Some(Tz::UTC));
+        assert_eq!(memo_of(cli), vec!["会議", "メモ"]);
+
+        let cli = parse_args(&["thn", "add", "--tz", "Europe/Berlin", "メモ"]);
+        assert_eq!(cli.tz, Some(Tz::Europe__Berlin));
+        assert_eq!(memo_of(cli), vec!["メモ"]);
+    }
+
+    #[test]
+    fn test_vault_before_subcommand() {
+        let cli = parse_args(&["thn", "--vault", "work", "--tz", "UTC", "list"]);
+        assert_eq!(cli.vault.as_deref(), Some("work"));
+        assert_eq!(cli.tz, Some(Tz::UTC));
+        assert!(matches!(cli.command, Some(Command::List(_))));
+
+        let cli = parse_args(&["thn", "--vault=personal", "会議", "メモ"]);
+        assert_eq!(cli.vault.as_deref(), Some("personal"));
+        assert_eq!(memo_of(cli), vec!["会議", "メモ"]);
+
+        let cli = parse_args(&["thn", "--vault", "work", "-c"]);
+        assert_eq!(cli.vault.as_deref(), Some("work"));
+        assert!(cli.config);
+    }
+
+    #[test]
+    fn test_parse_date_arg() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        assert_eq!(parse_date_arg("2026-10-16"), Ok(DateArg::Date(date)));
+        assert_eq!(parse_date_arg("Yesterday"), Ok(DateArg::Relative(-1)));
+        assert_eq!(parse_date_arg("today"), Ok(DateArg::Relative(0)));
+        assert_eq!(parse_date_arg("+3"), Ok(DateArg::Relative(3)));
+        assert!(parse_date_arg("-x").is_err());
+        assert!(parse_date_arg("2026-13-01").is_err());
+    }
+
+    #[test]
+    fn test_date_arg_resolve() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        assert_eq!(
+            DateArg::Relative(-1).resolve(today),
+            NaiveDate::from_ymd_opt(2026, 2, 28)
+        );
+        assert_eq!(
+            DateArg::Relative(2).resolve(today),
+            NaiveDate::from_ymd_opt(2026, 3, 3)
+        );
+        assert_eq!(DateArg::Relative(i64::MIN).resolve(today), None);
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(
+            parse_time("09:15"),
+            Ok(NaiveTime::from_hms_opt(9, 15, 0).unwrap())
+        );
+        assert_eq!(
+            parse_time("23:59:30"),
+            Ok(NaiveTime::from_hms_opt(23, 59, 30).unwrap())
+        );
+        assert!(parse_time("24:00").is_err());
+        assert!(parse_time("noon").is_err());
+    }
+}
@@ -0,0 +1,201 @@
+//! 現在日時の取得
+//!
+//! メモの記録時刻やデイリーノートの日付を決める「現在日時」を抽象化する。
+//! テストでは `FixedClock` で日時を固定し、`thn` コマンドでは環境変数 `THN_NOW` で上書きできる。
+//! タイムゾーンを指定した場合は、そのタイムゾーンの日時を「ローカル日時」として扱う。
+
+use std::fmt;
+
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+/// 現在日時を上書きする環境変数
+pub const NOW_ENV: &str = "THN_NOW";
+
+/// `THN_NOW` で受け付ける日時フォーマット
+const TIMESTAMP_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+];
+
+/// 現在日時の取得元
+///
+/// 今日の日付はthn設定の `day_start` によって変わるため、`Vault::today` で求める。
+pub trait Clock: fmt::Debug {
+    /// 現在のローカル日時を返す
+    fn now(&self) -> NaiveDateTime;
+}
+
+/// システム時計（ローカルタイムゾーン）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+}
+
+/// 指定したタイムゾーンのシステム時刻を返す時計
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZonedClock(pub Tz);
+
+impl Clock for ZonedClock {
+    fn now(&self) -> NaiveDateTime {
+        to_local(Utc::now(), Some(self.0))
+    }
+}
+
+/// タイムゾーンに応じたシステム時計を返す
+///
+/// `timezone` が `None` の場合はマシンのローカルタイムゾーンを使う。
+pub fn system_clock(timezone: Option<Tz>) -> Box<dyn Clock> {
+    match timezone {
+        Some(timezone) => Box::new(ZonedClock(timezone)),
+        None => Box::new(SystemClock),
+    }
+}
+
+/// 時刻をタイムゾーンのローカル日時に変換する
+///
+/// `timezone` が `None` の場合はマシンのローカルタイムゾーンに変換する。
+/// 夏時間の切り替えもタイムゾーンの規則に従う。
+pub fn to_local(instant: DateTime<Utc>, timezone: Option<Tz>) -> NaiveDateTime {
+    match timezone {
+        Some(timezone) => instant.with_timezone(&timezone).naive_local(),
+        None => instant.with_timezone(&Local).naive_local(),
+    }
+}
+
+/// 常に同じ日時を返す時計
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub NaiveDateTime);
+
+impl FixedClock {
+    /// `YYYY-MM-DDTHH:MM[:SS]` 形式（`T` の代わりに空白も可）の日時から作成する
+    ///
+    /// `2026-01-15T05:30:00Z` のようにオフセット付き（RFC 3339）の場合は、その時刻を
+    /// `timezone`（`None` の場合はマシンのローカルタイムゾーン）の日時に変換する。
+    /// オフセットなしの場合はそのままローカル日時として扱う。解釈できない場合は `None`。
+    pub fn parse(text: &str, timezone: Option<Tz>) -> Option<Self> {
+        let text = text.trim();
+        if let Ok(instant) = DateTime::parse_from_rfc3339(text) {
+            return Some(Self(to_local(instant.to_utc(), timezone)));
+        }
+
+        TIMESTAMP_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+            .map(Self)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> NaiveDateTime {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn timestamp(hour: u32, minute: u32, second: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 1, 15)
+            .unwrap()
+            .and_hms_opt(hour, minute, second)
+            .unwrap()
+    }
+
+    /// UTCの日時を作成するヘルパー関数
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().to_utc()
+    }
+
+    /// ローカル日時を作成するヘルパー関数
+    fn local(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_fixed_clock_parse() {
+        assert_eq!(
+            FixedClock::parse("2026-01-15T14:30:05", None),
+            Some(FixedClock(timestamp(14, 30, 5)))
+        );
+        assert_eq!(
+            FixedClock::parse("2026-01-15 14:30", Some(Tz::Asia__Tokyo)),
+            Some(FixedClock(timestamp(14, 30, 0)))
+        );
+        assert_eq!(FixedClock::parse("2026-01-15", None), None);
+        assert_eq!(FixedClock::parse("yesterday", None), None);
+    }
+
+    #[test]
+    fn test_fixed_clock_parse_with_offset() {
+        // 2026-01-15 23:30 UTC は東京では翌日の朝
+        assert_eq!(
+            FixedClock::parse("2026-01-15T23:30:00Z", Some(Tz::Asia__Tokyo)),
+            Some(FixedClock(local("2026-01-16T08:30:00")))
+        );
+        assert_eq!(
+            FixedClock::parse("2026-01-16T08:30:00+09:00", Some(Tz::America__New_York)),
+            Some(FixedClock(local("2026-01-15T18:30:00")))
+        );
+    }
+
+    #[test]
+    fn test_to_local_spring_forward() {
+        // ベルリンでは 2026-03-29 02:00 (CET) に 03:00 (CEST) へ進む
+        let berlin = Some(Tz::Europe__Berlin);
+        assert_eq!(
+            to_local(utc("2026-03-29T00:59:00Z"), berlin),
+            local("2026-03-29T01:59:00")
+        );
+        assert_eq!(
+            to_local(utc("2026-03-29T01:00:00Z"), berlin),
+            local("2026-03-29T03:00:00")
+        );
+    }
+
+    #[test]
+    fn test_to_local_fall_back() {
+        // ベルリンでは 2026-10-25 03:00 (CEST) に 02:00 (CET) へ戻り、02時台が2回ある
+        let berlin = Some(Tz::Europe__Berlin);
+        assert_eq!(
+            to_local(utc("2026-10-25T00:30:00Z"), berlin),
+            local("2026-10-25T02:30:00")
+        );
+        assert_eq!(
+            to_local(utc("2026-10-25T01:30:00Z"), berlin),
+            local("2026-10-25T02:30:00")
+        );
+        assert_eq!(
+            to_local(utc("2026-10-25T02:30:00Z"), berlin),
+            local("2026-10-25T03:30:00")
+        );
+    }
+
+    #[test]
+    fn test_to_local_dst_changes_date() {
+        // ニューヨークの夏時間中は UTC-4、冬時間は UTC-5
+        let new_york = Some(Tz::America__New_York);
+        assert_eq!(
+            to_local(utc("2026-07-01T03:30:00Z"), new_york),
+            local("2026-06-30T23:30:00")
+        );
+        assert_eq!(
+            to_local(utc("2026-12-01T04:30:00Z"), new_york),
+            local("2026-11-30T23:30:00")
+        );
+    }
+
+    #[test]
+    fn test_fixed_clock_now() {
+        let clock = FixedClock(timestamp(23, 59, 59));
+        assert_eq!(clock.now(), timestamp(23, 59, 59));
+    }
+}
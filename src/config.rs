@@ -2,23 +2,130 @@
 //!
 //! thn設定ファイル（`{config_dir}/thn/config.toml`）の読み書きを行う。
 //! パスはOSによって異なる（macOS: `~/Library/Application Support/`, Linux: `~/.config/`）。
+//! トップレベルの設定に加えて、`[vaults.<名前>]` テーブルで名前付きのVaultを定義できる。
 
+use std::collections::BTreeMap;
+use std::env;
 use std::fmt;
 use std::fs;
 use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
 
+use chrono::NaiveTime;
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
+use crate::atomic;
+use crate::clock;
+use crate::obsidian::{self, KnownVault, SaveMode};
+
+/// 名前付きVaultを選択する環境変数
+pub const VAULT_ENV: &str = "THN_VAULT";
+
 /// 設定構造体
-#[derive(Debug, Serialize, Deserialize)]
+///
+/// 今後の設定項目の追加に備えて、ライブラリからは `Config::new` で作成してから
+/// 各項目を設定する。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Config {
     /// Obsidian Vaultのパス
+    #[serde(default, skip_serializing_if = "is_empty_path")]
     pub vault_path: PathBuf,
+
+    /// メモを挿入するヘッダー（指定時はThinoの `InsertAfter` より優先）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insert_after: Option<String>,
+
+    /// メモの保存モード（指定時はThinoの設定より優先）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub save_mode: Option<SaveMode>,
+
+    /// FILEモードでメモを保存するフォルダ（指定時はThinoの設定より優先）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_mode_folder: Option<String>,
+
+    /// MULTIモードでメモを保存するファイル（指定時はThinoの設定より優先）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multi_mode_file: Option<String>,
+
+    /// CANVASモードでメモを保存するキャンバスファイル（指定時はThinoの設定より優先）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canvas_mode_file: Option<String>,
+
+    /// 1日の始まりの時刻（例: "04:00"）。この時刻より前のメモは前日のデイリーノートに保存する
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day_start: Option<NaiveTime>,
+
+    /// メモの時刻フォーマット（moment.js形式、指定時はThinoの `TimeFormat` より優先）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<String>,
+
+    /// 書き込み先に同期クライアントの競合コピーがある場合の動作（未指定時は警告）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_sync_conflict: Option<SyncConflictPolicy>,
+
+    /// デイリーノートの `{名前} 2.md` のような番号付きのファイルもiCloud Driveの
+    /// 競合コピーとみなすか（未指定時は `false`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icloud_conflicts: Option<bool>,
+
+    /// 記録日時に使うIANAタイムゾーン（例: "Asia/Tokyo"、未指定時はマシンのローカルタイムゾーン）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<Tz>,
+}
+
+/// 書き込み先に同期クライアントの競合コピーがある場合の動作
+///
+/// thn設定ファイルでは小文字（`warn` / `refuse` / `ignore`）で指定する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncConflictPolicy {
+    /// 警告を表示して書き込む
+    #[default]
+    Warn,
+    /// 書き込まずにエラーにする
+    Refuse,
+    /// 競合コピーを確認しない
+    Ignore,
+}
+
+impl SyncConflictPolicy {
+    /// 設定ファイルでの表記を返す
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SyncConflictPolicy::Warn => "warn",
+            SyncConflictPolicy::Refuse => "refuse",
+            SyncConflictPolicy::Ignore => "ignore",
+        }
+    }
+}
+
+/// 設定ファイルの内容
+///
+/// トップレベルの設定は名前なしのVault（従来の単一Vault形式）として扱う。
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ConfigFile {
+    /// 既定で使う名前付きVault（未指定時は名前なしのVault）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_vault: Option<String>,
+
+    /// 名前なしのVaultの設定
+    #[serde(flatten)]
+    pub default: Config,
+
+    /// 名前付きVaultの設定
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vaults: BTreeMap<String, Config>,
+}
+
+fn is_empty_path(path: &Path) -> bool {
+    path.as_os_str().is_empty()
 }
 
 /// 設定関連のエラー
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ConfigError {
     /// IO操作に失敗
     Io(io::Error),
@@ -32,6 +139,12 @@ pub enum ConfigError {
     VaultNotFound(PathBuf),
     /// ObsidianのVaultではない（.obsidianディレクトリがない）
     NotObsidianVault(PathBuf),
+    /// 指定された名前のVaultが設定されていない（名前, 設定済みのVault名）
+    UnknownVault(String, Vec<String>),
+    /// 既定のVaultが設定されていない（設定済みのVault名）
+    NoDefaultVault(Vec<String>),
+    /// 名前付きVaultの `vault_path` が設定されていない（名前）
+    MissingVaultPath(String),
 }
 
 impl fmt::Display for ConfigError {
@@ -41,12 +154,28 @@ impl fmt::Display for ConfigError {
             ConfigError::TomlDeserialize(err) => write!(f, "toml parse error: {err}"),
             ConfigError::TomlSerialize(err) => write!(f, "toml serialize error: {err}"),
             ConfigError::NotFound => {
-                write!(f, "not configured. run 'thn --init [<PATH>]' first")
+                write!(f, "not configured. run 'thn init [<PATH>]' first")
             }
             ConfigError::VaultNotFound(path) => write!(f, "vault not found: {}", path.display()),
             ConfigError::NotObsidianVault(path) => {
                 write!(f, "not an obsidian vault: {}", path.display())
             }
+            ConfigError::UnknownVault(name, names) if names.is_empty() => write!(
+                f,
+                "unknown vault: {name}. run 'thn init --name {name} <PATH>' to add it"
+            ),
+            ConfigError::UnknownVault(name, names) => {
+                write!(f, "unknown vault: {name} (available: {})", names.join(", "))
+            }
+            ConfigError::NoDefaultVault(names) => write!(
+                f,
+                "no default vault. use --vault <NAME> (available: {})",
+                names.join(", ")
+            ),
+            ConfigError::MissingVaultPath(name) => write!(
+                f,
+                "vault_path is not set for vault: {name}. run 'thn init --name {name} <PATH>'"
+            ),
         }
     }
 }
@@ -59,7 +188,10 @@ impl std::error::Error for ConfigError {
             ConfigError::TomlSerialize(err) => Some(err),
             ConfigError::NotFound
             | ConfigError::VaultNotFound(_)
-            | ConfigError::NotObsidianVault(_) => None,
+            | ConfigError::NotObsidianVault(_)
+            | ConfigError::UnknownVault(..)
+            | ConfigError::NoDefaultVault(_)
+            | ConfigError::MissingVaultPath(_) => None,
         }
     }
 }
@@ -98,6 +230,53 @@ pub fn config_path() -> PathBuf {
         .join("config.toml")
 }
 
+/// 設定ファイルから既定のVaultの設定を読み込む
+///
+/// `thn` コマンドと同じく、環境変数 `THN_VAULT` が空でなければその名前付きVaultを読み込む。
+///
+/// # Errors
+///
+/// `load_vault` と同じ
+pub fn load() -> Result<Config, ConfigError> {
+    load_vault(vault_from_env().as_deref())
+}
+
+/// 環境変数 `THN_VAULT` で選択された名前付きVaultの名前を返す（未設定または空なら `None`）
+pub fn vault_from_env() -> Option<String> {
+    env::var(VAULT_ENV).ok().filter(|name| !name.is_empty())
+}
+
+/// 設定ファイルからVaultの設定を読み込む
+///
+/// # 引数
+///
+/// * `name` - 名前付きVaultの名前（`None` の場合は既定のVault）
+///
+/// # Errors
+///
+/// - `ConfigError::UnknownVault` - 指定された名前のVaultが設定されていない場合
+/// - `ConfigError::NoDefaultVault` - 名前を省略したが既定のVaultが設定されていない場合
+/// - `ConfigError::MissingVaultPath` - 名前付きVaultの `vault_path` が設定されていない場合
+/// - その他、`load_file` と同じ
+pub fn load_vault(name: Option<&str>) -> Result<Config, ConfigError> {
+    load_file()?.into_vault(name)
+}
+
+/// 設定ファイルを読み込む（存在しない場合は空の設定を返す）
+///
+/// 既存の設定を保持したまま書き換える場合に使う。パースに失敗した設定ファイルを
+/// 上書きしないよう、存在しない場合以外のエラーはそのまま返す。
+///
+/// # Errors
+///
+/// `ConfigError::NotFound` 以外は `load_file` と同じ
+pub fn load_file_or_default() -> Result<ConfigFile, ConfigError> {
+    match load_file() {
+        Err(ConfigError::NotFound) => Ok(ConfigFile::default()),
+        result => result,
+    }
+}
+
 /// 設定ファイルを読み込む
 ///
 /// # Errors
@@ -105,11 +284,11 @@ pub fn config_path() -> PathBuf {
 /// - `ConfigError::NotFound` - 設定ファイルが存在しない場合
 /// - `ConfigError::Io` - ファイル読み込みに失敗した場合
 /// - `ConfigError::TomlDeserialize` - TOMLのパースに失敗した場合
-pub fn load() -> Result<Config, ConfigError> {
+pub fn load_file() -> Result<ConfigFile, ConfigError> {
     let path = config_path();
     let content = fs::read_to_string(&path)?;
-    let config: Config = toml::from_str(&content)?;
-    Ok(config)
+    let file: ConfigFile = toml::from_str(&content)?;
+    Ok(file)
 }
 
 /// Vaultパスを検証する
@@ -168,25 +347,161 @@ pub fn parse_vault_path(input: &str) -> Result<PathBuf, io::Error> {
 
 /// 対話形式でVaultパスを入力
 ///
-/// "Vault path: " を表示してstdinから読み取る。
+/// Obsidianに登録されたVault（`obsidian.json`）があれば、最後に開いた日時とともに
+/// 番号付きで表示し、番号またはパスを受け付ける。なければ "Vault path: " を表示する。
+/// 最後に開いた日時は、既定のVaultの `timezone`（未設定ならローカルタイムゾーン）で表示する。
 /// `~` で始まるパスはホームディレクトリに展開される。
 ///
 /// # Errors
 ///
 /// - 標準入力からの読み取りに失敗した場合
 /// - 入力が空の場合
+/// - 一覧にない番号が入力された場合
 pub fn prompt_vault_path() -> Result<PathBuf, io::Error> {
-    print!("Vault path: ");
+    let timezone = load().ok().and_then(|config| config.timezone);
+    let known_vaults = obsidian::global_config_path()
+        .map(|path| obsidian::load_known_vaults(&path))
+        .unwrap_or_default();
+
+    if known_vaults.is_empty() {
+        print!("Vault path: ");
+    } else {
+        print!("{}", format_known_vaults(&known_vaults, timezone));
+        print!("Vault number or path: ");
+    }
     io::stdout().flush()?;
 
     let stdin = io::stdin();
     let mut line = String::new();
     stdin.lock().read_line(&mut line)?;
 
-    parse_vault_path(&line)
+    select_vault_path(&line, &known_vaults)
+}
+
+/// 登録済みのVaultを番号付きの一覧に変換する
+///
+/// 最後に開いた日時は `timezone`（`None` の場合はマシンのローカルタイムゾーン）で表示する。
+fn format_known_vaults(vaults: &[KnownVault], timezone: Option<Tz>) -> String {
+    let mut output = String::from("Obsidian vaults:\n");
+    for (index, vault) in vaults.iter().enumerate() {
+        output.push_str(&format!("  {}) {}", index + 1, vault.path.display()));
+        if let Some(last_opened) = vault.last_opened {
+            let local = clock::to_local(last_opened, timezone);
+            output.push_str(&format!(
+                " (last opened {})",
+                local.format("%Y-%m-%d %H:%M")
+            ));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// 入力された番号またはパスからVaultパスを求める
+///
+/// 登録済みのVaultがある場合、数字のみの入力は一覧の番号（1始まり）として扱う。
+///
+/// # Errors
+///
+/// - 入力が空の場合
+/// - 一覧にない番号が入力された場合
+fn select_vault_path(input: &str, vaults: &[KnownVault]) -> Result<PathBuf, io::Error> {
+    match input.trim().parse::<usize>() {
+        Ok(number) if !vaults.is_empty() => number
+            .checked_sub(1)
+            .and_then(|index| vaults.get(index))
+            .map(|vault| vault.path.clone())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid vault number: {number}"),
+                )
+            }),
+        _ => parse_vault_path(input),
+    }
 }
 
 impl Config {
+    /// Vaultのパスだけを指定した設定を作成する
+    ///
+    /// その他の項目は未指定（Thinoの設定や既定値に従う）になる。
+    ///
+    /// # 引数
+    ///
+    /// * `vault_path` - Obsidian Vaultのパス
+    pub fn new(vault_path: impl Into<PathBuf>) -> Self {
+        Self {
+            vault_path: vault_path.into(),
+            ..Self::default()
+        }
+    }
+
+    /// 設定を名前なしのVaultの設定として設定ファイルに保存する
+    ///
+    /// 設定ファイルの名前付きVaultと `default_vault` は保持する。
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::TomlDeserialize` - 既存の設定ファイルのパースに失敗した場合
+    /// - その他、`ConfigFile::save` と同じ
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let mut file = load_file_or_default()?;
+        file.default = self.clone();
+        file.save()
+    }
+}
+
+impl ConfigFile {
+    /// Vaultの設定を取り出す
+    ///
+    /// `name` が `None` の場合は `default_vault` のVault、それも未指定なら名前なしのVaultを返す。
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::UnknownVault` - 指定された名前のVaultが設定されていない場合
+    /// - `ConfigError::NoDefaultVault` - 名前を省略したが既定のVaultが設定されていない場合
+    /// - `ConfigError::MissingVaultPath` - 名前付きVaultの `vault_path` が設定されていない場合
+    pub fn into_vault(mut self, name: Option<&str>) -> Result<Config, ConfigError> {
+        let name = name.map(str::to_string).or(self.default_vault.take());
+        match name {
+            Some(name) => match self.vaults.remove(&name) {
+                Some(config) if is_empty_path(&config.vault_path) => {
+                    Err(ConfigError::MissingVaultPath(name))
+                }
+                Some(config) => Ok(config),
+                None => Err(ConfigError::UnknownVault(
+                    name,
+                    self.vaults.into_keys().collect(),
+                )),
+            },
+            None if is_empty_path(&self.default.vault_path) => Err(ConfigError::NoDefaultVault(
+                self.vaults.into_keys().collect(),
+            )),
+            None => Ok(self.default),
+        }
+    }
+
+    /// VaultのパスをVaultの設定に登録する
+    ///
+    /// Vaultの他の設定項目は保持する。名前付きVaultを追加した際に、名前なしのVaultも
+    /// `default_vault` も設定されていなければ、そのVaultを既定にする。
+    ///
+    /// # 引数
+    ///
+    /// * `name` - 名前付きVaultの名前（`None` の場合は名前なしのVault）
+    /// * `vault_path` - Obsidian Vaultのパス
+    pub fn set_vault_path(&mut self, name: Option<&str>, vault_path: PathBuf) {
+        let Some(name) = name else {
+            self.default.vault_path = vault_path;
+            return;
+        };
+
+        if self.default_vault.is_none() && is_empty_path(&self.default.vault_path) {
+            self.default_vault = Some(name.to_string());
+        }
+        self.vaults.entry(name.to_string()).or_default().vault_path = vault_path;
+    }
+
     /// 設定をファイルに保存する
     ///
     /// ディレクトリが存在しない場合は作成する。
@@ -204,7 +519,7 @@ impl Config {
         }
 
         let content = toml::to_string_pretty(self)?;
-        fs::write(&path, content)?;
+        atomic::write(&path, content)?;
         Ok(())
     }
 }
@@ -223,6 +538,7 @@ mod tests {
     fn test_config_serialize_deserialize() {
         let config = Config {
             vault_path: PathBuf::from("/path/to/vault"),
+            ..Config::default()
         };
 
         let toml_str = toml::to_string(&config).unwrap();
@@ -231,12 +547,217 @@ mod tests {
         assert_eq!(parsed.vault_path, config.vault_path);
     }
 
+    #[test]
+    fn test_config_new() {
+        let config = Config::new("/path/to/vault");
+
+        assert_eq!(config.vault_path, PathBuf::from("/path/to/vault"));
+        assert!(config.insert_after.is_none());
+        assert!(config.timezone.is_none());
+    }
+
+    #[test]
+    fn test_config_insert_after_optional() {
+        let config: Config = toml::from_str(r#"vault_path = "/path/to/vault""#).unwrap();
+        assert_eq!(config.insert_after, None);
+
+        // 未設定の場合は出力しない
+        let toml_str = toml::to_string(&config).unwrap();
+        assert!(!toml_str.contains("insert_after"));
+    }
+
+    #[test]
+    fn test_config_insert_after() {
+        let toml_str = "vault_path = \"/path/to/vault\"\ninsert_after = \"## Journal\"\n";
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.insert_after.as_deref(), Some("## Journal"));
+    }
+
+    #[test]
+    fn test_config_save_mode() {
+        let toml_str = "vault_path = \"/path/to/vault\"\nsave_mode = \"file\"\n";
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.save_mode, Some(SaveMode::File));
+    }
+
+    #[test]
+    fn test_config_save_mode_invalid() {
+        let toml_str = "vault_path = \"/path/to/vault\"\nsave_mode = \"unknown\"\n";
+        let result: Result<Config, _> = toml::from_str(toml_str);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_config_day_start() {
+        let toml_str = "vault_path = \"/path/to/vault\"\nday_start = \"04:00\"\n";
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.day_start, NaiveTime::from_hms_opt(4, 0, 0));
+
+        let toml_str = "vault_path = \"/path/to/vault\"\nday_start = \"4am\"\n";
+        let result: Result<Config, _> = toml::from_str(toml_str);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_config_on_sync_conflict() {
+        let config: Config = toml::from_str("vault_path = \"/path/to/vault\"\n").unwrap();
+        assert_eq!(config.on_sync_conflict, None);
+
+        let toml_str = "vault_path = \"/path/to/vault\"\non_sync_conflict = \"refuse\"\n";
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.on_sync_conflict, Some(SyncConflictPolicy::Refuse));
+
+        let toml_str = "vault_path = \"/path/to/vault\"\non_sync_conflict = \"merge\"\n";
+        let result: Result<Config, _> = toml::from_str(toml_str);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_config_timezone() {
+        let toml_str = "vault_path = \"/path/to/vault\"\ntimezone = \"Europe/Berlin\"\n";
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.timezone, Some(Tz::Europe__Berlin));
+
+        let toml_str = toml::to_string(&config).unwrap();
+        assert!(toml_str.contains("timezone = \"Europe/Berlin\""));
+
+        let toml_str = "vault_path = \"/path/to/vault\"\ntimezone = \"Mars/Olympus\"\n";
+        let result: Result<Config, _> = toml::from_str(toml_str);
+        assert!(result.is_err());
+    }
+
+    // ========================================
+    // 名前付きVault テスト
+    // ========================================
+
+    const PROFILES: &str = r###"
+vault_path = "/vaults/main"
+day_start = "04:00"
+
+[vaults.work]
+vault_path = "/vaults/work"
+insert_after = "## Log"
+
+[vaults.personal]
+vault_path = "/vaults/personal"
+"###;
+
+    #[test]
+    fn test_config_file_vaults() {
+        let file: ConfigFile = toml::from_str(PROFILES).unwrap();
+        assert_eq!(file.default.vault_path, PathBuf::from("/vaults/main"));
+        assert_eq!(file.default.day_start, NaiveTime::from_hms_opt(4, 0, 0));
+        assert_eq!(
+            file.vaults.keys().collect::<Vec<_>>(),
+            vec!["personal", "work"]
+        );
+
+        let work = file.into_vault(Some("work")).unwrap();
+        assert_eq!(work.vault_path, PathBuf::from("/vaults/work"));
+        assert_eq!(work.insert_after.as_deref(), Some("## Log"));
+        // トップレベルの設定は名前付きVaultには引き継がない
+        assert_eq!(work.day_start, None);
+    }
+
+    #[test]
+    fn test_config_file_default_vault() {
+        let file: ConfigFile = toml::from_str(PROFILES).unwrap();
+        let config = file.into_vault(None).unwrap();
+        assert_eq!(config.vault_path, PathBuf::from("/vaults/main"));
+
+        let toml_str = format!("default_vault = \"personal\"\n{PROFILES}");
+        let file: ConfigFile = toml::from_str(&toml_str).unwrap();
+        let config = file.into_vault(None).unwrap();
+        assert_eq!(config.vault_path, PathBuf::from("/vaults/personal"));
+    }
+
+    #[test]
+    fn test_config_file_unknown_vault() {
+        let file: ConfigFile = toml::from_str(PROFILES).unwrap();
+        let err = file.into_vault(Some("school")).unwrap_err();
+        assert!(matches!(&err, ConfigError::UnknownVault(name, _) if name == "school"));
+        assert_eq!(
+            err.to_string(),
+            "unknown vault: school (available: personal, work)"
+        );
+
+        let err = ConfigFile::default().into_vault(Some("work")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown vault: work. run 'thn init --name work <PATH>' to add it"
+        );
+    }
+
+    #[test]
+    fn test_config_file_vault_without_path() {
+        let toml_str = "default_vault = \"work\"\n[vaults.work]\ninsert_after = \"## Log\"\n";
+        let file: ConfigFile = toml::from_str(toml_str).unwrap();
+        let err = file.into_vault(None).unwrap_err();
+        assert!(matches!(&err, ConfigError::MissingVaultPath(name) if name == "work"));
+        assert_eq!(
+            err.to_string(),
+            "vault_path is not set for vault: work. run 'thn init --name work <PATH>'"
+        );
+    }
+
+    #[test]
+    fn test_config_file_no_default_vault() {
+        let toml_str = "[vaults.work]\nvault_path = \"/vaults/work\"\n";
+        let file: ConfigFile = toml::from_str(toml_str).unwrap();
+        let err = file.into_vault(None).unwrap_err();
+        assert!(matches!(err, ConfigError::NoDefaultVault(_)));
+        assert_eq!(
+            err.to_string(),
+            "no default vault. use --vault <NAME> (available: work)"
+        );
+    }
+
+    #[test]
+    fn test_config_file_invalid_top_level_setting() {
+        let toml_str = "save_mode = \"unknown\"\n[vaults.work]\nvault_path = \"/w\"\n";
+        let result: Result<ConfigFile, _> = toml::from_str(toml_str);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_config_file_set_vault_path() {
+        let mut file = ConfigFile::default();
+        file.set_vault_path(Some("work"), PathBuf::from("/vaults/work"));
+        // 最初の名前付きVaultは既定になる
+        assert_eq!(file.default_vault.as_deref(), Some("work"));
+
+        file.set_vault_path(Some("personal"), PathBuf::from("/vaults/personal"));
+        assert_eq!(file.default_vault.as_deref(), Some("work"));
+
+        file.vaults.get_mut("work").unwrap().insert_after = Some("## Log".to_string());
+        file.set_vault_path(Some("work"), PathBuf::from("/vaults/work2"));
+        let toml_str = toml::to_string(&file).unwrap();
+        assert_eq!(
+            toml_str,
+            "default_vault = \"work\"\n\n\
+             [vaults.personal]\nvault_path = \"/vaults/personal\"\n\n\
+             [vaults.work]\nvault_path = \"/vaults/work2\"\ninsert_after = \"## Log\"\n"
+        );
+    }
+
+    #[test]
+    fn test_config_file_set_unnamed_vault_path() {
+        let mut file: ConfigFile = toml::from_str(PROFILES).unwrap();
+        file.set_vault_path(None, PathBuf::from("/vaults/new"));
+        assert_eq!(file.default_vault, None);
+
+        let parsed: ConfigFile = toml::from_str(&toml::to_string(&file).unwrap()).unwrap();
+        assert_eq!(parsed.default.vault_path, PathBuf::from("/vaults/new"));
+        assert_eq!(parsed.default.day_start, NaiveTime::from_hms_opt(4, 0, 0));
+        assert_eq!(parsed.vaults.len(), 2);
+    }
+
     #[test]
     fn test_config_error_display() {
         let err = ConfigError::NotFound;
         assert_eq!(
             err.to_string(),
-            "not configured. run 'thn --init [<PATH>]' first"
+            "not configured. run 'thn init [<PATH>]' first"
         );
     }
 
@@ -331,4 +852,58 @@ mod tests {
         let result = parse_vault_path("   ");
         assert!(result.is_err());
     }
+
+    // ========================================
+    // Vaultの選択 テスト
+    // ========================================
+
+    fn known_vaults() -> Vec<KnownVault> {
+        vec![
+            KnownVault {
+                path: PathBuf::from("/vaults/work"),
+                last_opened: chrono::DateTime::from_timestamp(1768435200, 0),
+            },
+            KnownVault {
+                path: PathBuf::from("/vaults/archive"),
+                last_opened: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_known_vaults() {
+        assert_eq!(
+            format_known_vaults(&known_vaults(), Some(Tz::Asia__Tokyo)),
+            "Obsidian vaults:\n  1) /vaults/work (last opened 2026-01-15 09:00)\n  2) /vaults/archive\n"
+        );
+    }
+
+    #[test]
+    fn test_select_vault_path_by_number() {
+        let vaults = known_vaults();
+        assert_eq!(
+            select_vault_path("2\n", &vaults).unwrap(),
+            PathBuf::from("/vaults/archive")
+        );
+
+        let err = select_vault_path("3", &vaults).unwrap_err();
+        assert_eq!(err.to_string(), "invalid vault number: 3");
+        assert!(select_vault_path("0", &vaults).is_err());
+    }
+
+    #[test]
+    fn test_select_vault_path_manual_entry() {
+        let vaults = known_vaults();
+        assert_eq!(
+            select_vault_path("/other/vault\n", &vaults).unwrap(),
+            PathBuf::from("/other/vault")
+        );
+        assert!(select_vault_path("  \n", &vaults).is_err());
+
+        // 登録済みのVaultがない場合、数字はパスとして扱う
+        assert_eq!(
+            select_vault_path("2024", &[]).unwrap(),
+            PathBuf::from("2024")
+        );
+    }
 }
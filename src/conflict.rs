@@ -0,0 +1,208 @@
+//! 同期クライアントの競合コピーの検出
+//!
+//! Obsidian Sync・Syncthing・iCloudなどの同期クライアントは、同じファイルが複数の端末で
+//! 変更されると競合コピーを作成する。競合が解消されないまま本体のノートに追記し続けると
+//! 差分が広がるため、書き込み前にノートと同じフォルダの競合コピーを探す。
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// ノートの競合コピーを返す
+///
+/// ノートと同じフォルダにある、次の命名規則のファイルを競合コピーとみなす。
+///
+/// - `{名前} (conflict).md`、`{名前} (… conflicted copy …).md` など、括弧内に
+///   "conflict" を含むもの（Obsidian Sync、Dropbox、Nextcloudなど）
+/// - `{名前}.sync-conflict-….md`（Syncthing）
+/// - `numbered` が `true` の場合、`{名前} 2.md` のように番号が付いたもの（iCloud Drive）
+///
+/// 番号付きのファイルは通常のノートの名前としても使われるため、既定では対象にしない。
+///
+/// # 引数
+///
+/// * `path` - 書き込み先のノートのパス
+/// * `numbered` - 番号付きのファイルも競合コピーとみなすか
+///
+/// # 戻り値
+///
+/// 名前順の競合コピーのパス。フォルダが存在しない場合は空。
+///
+/// # Errors
+///
+/// フォルダの読み込みに失敗した場合
+pub(crate) fn find_conflict_copies(path: &Path, numbered: bool) -> io::Result<Vec<PathBuf>> {
+    let (Some(dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return Ok(Vec::new());
+    };
+    let Some(file_name) = file_name.to_str() else {
+        return Ok(Vec::new());
+    };
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+        _ => (file_name, String::new()),
+    };
+
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut copies = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name
+            .to_str()
+            .is_some_and(|name| is_conflict_copy(name, stem, &extension, numbered))
+        {
+            copies.push(dir.join(name));
+        }
+    }
+    copies.sort();
+    Ok(copies)
+}
+
+/// ファイル名がノート（`{stem}{extension}`）の競合コピーかどうかを判定
+fn is_conflict_copy(name: &str, stem: &str, extension: &str, numbered: bool) -> bool {
+    let Some(middle) = name
+        .strip_prefix(stem)
+        .and_then(|rest| rest.strip_suffix(extension))
+    else {
+        return false;
+    };
+
+    if let Some(syncthing) = middle.strip_prefix(".sync-conflict-") {
+        return !syncthing.is_empty();
+    }
+
+    let Some(suffix) = middle.strip_prefix(' ') else {
+        return false;
+    };
+    if let Some(label) = suffix
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return label.to_lowercase().contains("conflict");
+    }
+    // iCloud Driveは "名前 2.md" のように2以降の番号を付ける
+    numbered && suffix.parse::<u32>().is_ok_and(|number| number >= 2) && !suffix.starts_with('0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_conflict_copy() {
+        let conflicts = [
+            "2026-10-18 (conflict).md",
+            "2026-10-18 (Conflicted copy 2026-10-18 091500).md",
+            "2026-10-18 (Taro's conflicted copy 2026-10-18).md",
+            "2026-10-18.sync-conflict-20261018-091500-ABCDEFG.md",
+        ];
+        for name in conflicts {
+            assert!(is_conflict_copy(name, "2026-10-18", ".md", false), "{name}");
+            assert!(is_conflict_copy(name, "2026-10-18", ".md", true), "{name}");
+        }
+
+        let others = [
+            "2026-10-18.md",
+            "2026-10-18 (1).md",
+            "2026-10-18 1.md",
+            "2026-10-18 02.md",
+            "2026-10-18 notes.md",
+            "2026-10-18 (conflict).canvas",
+            "2026-10-18.sync-conflict-.md",
+            "2026-10-19 (conflict).md",
+            "2026-10-182.md",
+        ];
+        for name in others {
+            assert!(!is_conflict_copy(name, "2026-10-18", ".md", true), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_is_conflict_copy_numbered() {
+        // iCloud Driveの番号付きコピーは指定した場合のみ
+        assert!(is_conflict_copy(
+            "2026-10-18 2.md",
+            "2026-10-18",
+            ".md",
+            true
+        ));
+        assert!(is_conflict_copy(
+            "2026-10-18 13.md",
+            "2026-10-18",
+            ".md",
+            true
+        ));
+        assert!(!is_conflict_copy(
+            "2026-10-18 2.md",
+            "2026-10-18",
+            ".md",
+            false
+        ));
+    }
+
+    #[test]
+    fn test_numbered_files_are_not_conflicts_by_default() {
+        let dir = tempdir().unwrap();
+        for name in ["Thino.md", "Thino 2.md", "Board.canvas", "Board 2.canvas"] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+
+        // MULTIモードのファイルやキャンバスの隣にある番号付きのファイルは通常のファイル
+        assert!(
+            find_conflict_copies(&dir.path().join("Thino.md"), false)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            find_conflict_copies(&dir.path().join("Board.canvas"), false)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_find_conflict_copies() {
+        let dir = tempdir().unwrap();
+        for name in [
+            "2026-10-18.md",
+            "2026-10-18 (conflict).md",
+            "2026-10-18.sync-conflict-20261018-091500-ABCDEFG.md",
+            "2026-10-18 2.md",
+            "2026-10-17 (conflict).md",
+            "Thino.md",
+        ] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        let path = dir.path().join("2026-10-18.md");
+
+        assert_eq!(
+            find_conflict_copies(&path, false).unwrap(),
+            vec![
+                dir.path().join("2026-10-18 (conflict).md"),
+                dir.path()
+                    .join("2026-10-18.sync-conflict-20261018-091500-ABCDEFG.md"),
+            ]
+        );
+        assert_eq!(find_conflict_copies(&path, true).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_find_conflict_copies_missing_folder() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Daily").join("2026-10-18.md");
+
+        assert!(find_conflict_copies(&path, true).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+//! エディタでのメモ作成
+//!
+//! `$VISUAL` / `$EDITOR` で一時ファイルを開き、保存された内容をメモとして取得する。
+//! `# ` で始まる行はgitのコミットメッセージと同様にコメントとして除去する。
+//! `#idea` のように `#` の直後に空白がない行はObsidianのタグなので残す。
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+use thn::MemoError;
+
+/// コメント行の接頭辞
+const COMMENT_PREFIX: char = '#';
+
+/// エディタに表示する案内文
+const HELP_TEXT: &str = "\
+# Write your memo. Lines starting with '# ' will be ignored,
+# and an empty memo aborts.
+";
+
+/// 環境変数が未設定の場合に使用するエディタ
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// エディタでメモを作成する
+///
+/// 一時ファイルを作成してエディタで開き、保存された内容からコメント行を除去して返す。
+/// 一時ファイルは成功・失敗にかかわらず削除される。
+///
+/// # 引数
+///
+/// * `initial` - エディタに初期表示する内容（空文字列可）
+///
+/// # Errors
+///
+/// - `MemoError::Io` - 一時ファイルの操作やエディタの起動に失敗した場合
+/// - `MemoError::EditorFailed` - エディタが非ゼロで終了した場合
+/// - `MemoError::EmptyMemo` - コメント除去後の内容が空の場合
+pub fn compose_memo(initial: &str) -> Result<String, MemoError> {
+    let editor = editor_command();
+
+    // ドロップ時に削除されるよう、パスのみ保持してファイルハンドルは閉じる
+    let temp_path = tempfile::Builder::new()
+        .prefix("thn-")
+        .suffix(".md")
+        .tempfile()?
+        .into_temp_path();
+
+    let buffer = if initial.is_empty() {
+        format!("\n{HELP_TEXT}")
+    } else {
+        format!("{initial}\n\n{HELP_TEXT}")
+    };
+    fs::write(&temp_path, buffer)?;
+
+    let status = run_editor(&editor, &temp_path)?;
+    if !status.success() {
+        return Err(MemoError::EditorFailed(editor, status));
+    }
+
+    let edited = fs::read_to_string(&temp_path)?;
+    let content = strip_comments(&edited);
+    if content.is_empty() {
+        return Err(MemoError::EmptyMemo);
+    }
+
+    Ok(content)
+}
+
+/// 使用するエディタのコマンドを返す
+///
+/// `$VISUAL`、`$EDITOR` の順に参照し、どちらも未設定なら既定のエディタを返す。
+fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|key| env::var(key).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// エディタを起動して終了を待つ
+///
+/// `code --wait` のように引数を含むエディタ指定に対応するため、シェル経由で起動する。
+#[cfg(not(windows))]
+fn run_editor(editor: &str, path: &Path) -> std::io::Result<ExitStatus> {
+    Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$@\""))
+        .arg(editor)
+        .arg(path)
+        .status()
+}
+
+/// エディタを起動して終了を待つ
+#[cfg(windows)]
+fn run_editor(editor: &str, path: &Path) -> std::io::Result<ExitStatus> {
+    Command::new("cmd").arg("/C").arg(editor).arg(path).status()
+}
+
+/// コメント行を除去し、前後の空行を取り除く
+fn strip_comments(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().filter(|line| !is_comment(line)).collect();
+
+    lines
+        .join("\n")
+        .trim_matches(['\n', '\r'])
+        .trim_end()
+        .to_string()
+}
+
+/// `#` のみ、または `#` の直後に空白が続く行をコメントとみなす（`#tag` はタグ）
+fn is_comment(line: &str) -> bool {
+    line.strip_prefix(COMMENT_PREFIX)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_comments_removes_comment_lines() {
+        let text = "メモ\n# comment\n";
+        assert_eq!(strip_comments(text), "メモ");
+    }
+
+    #[test]
+    fn test_strip_comments_preserves_multiline() {
+        let text = "TODO:\n- Task 1\n- Task 2\n\n# comment\n# comment\n";
+        assert_eq!(strip_comments(text), "TODO:\n- Task 1\n- Task 2");
+    }
+
+    #[test]
+    fn test_strip_comments_trims_surrounding_blank_lines() {
+        let text = "\n\nメモ\n\n\n";
+        assert_eq!(strip_comments(text), "メモ");
+    }
+
+    #[test]
+    fn test_strip_comments_keeps_indented_hash() {
+        // 行頭以外の '#' はコメントとして扱わない
+        let text = "メモ #tag\n  # indented\n";
+        assert_eq!(strip_comments(text), "メモ #tag\n  # indented");
+    }
+
+    #[test]
+    fn test_strip_comments_keeps_tag_lines() {
+        // 行頭の '#tag' はObsidianのタグ
+        let text = "#idea buy milk\n#work/meeting\n#\n# comment\n";
+        assert_eq!(strip_comments(text), "#idea buy milk\n#work/meeting");
+    }
+
+    #[test]
+    fn test_strip_comments_only_comments_is_empty() {
+        assert_eq!(strip_comments(&format!("\n{HELP_TEXT}")), "");
+    }
+}
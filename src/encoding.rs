@@ -0,0 +1,125 @@
+//! ノートの改行コードとBOMの保持
+//!
+//! Windowsで作成したノートはCRLF改行やUTF-8のBOM付きのことがある。メモの挿入は
+//! LF改行のテキストとして行い、書き戻すときに元の改行コードとBOMに戻す。
+//! UTF-8として読めないノートは、内容を失わないようエラーにする。
+
+use std::str::{self, Utf8Error};
+
+/// UTF-8のBOM
+const BOM: &str = "\u{feff}";
+
+/// 読み込んだノートの内容と書式
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct NoteText {
+    /// BOMを除き、CRLF改行のノートではLF改行にした内容
+    pub(crate) content: String,
+    /// 先頭にBOMがあったか
+    bom: bool,
+    /// CRLF改行のノートか
+    crlf: bool,
+}
+
+impl NoteText {
+    /// ノートのバイト列を読み取る
+    ///
+    /// すべての改行がCRLFの場合だけCRLF改行のノートとして扱う。LFとCRLFが混在する
+    /// ノートは変換せずにそのまま扱い、既存の行を書き換えないようにする。
+    ///
+    /// # Errors
+    ///
+    /// UTF-8として不正なバイト列の場合
+    pub(crate) fn decode(bytes: &[u8]) -> Result<Self, Utf8Error> {
+        let text = str::from_utf8(bytes)?;
+        let (text, bom) = match text.strip_prefix(BOM) {
+            Some(rest) => (rest, true),
+            None => (text, false),
+        };
+
+        let crlf =
+            text.contains("\r\n") && text.matches('\n').count() == text.matches("\r\n").count();
+        let content = if crlf {
+            text.replace("\r\n", "\n")
+        } else {
+            text.to_string()
+        };
+
+        Ok(NoteText { content, bom, crlf })
+    }
+
+    /// 読み込んだノートと同じ改行コードとBOMで内容を書き出す
+    ///
+    /// # 引数
+    ///
+    /// * `content` - LF改行の内容
+    pub(crate) fn encode(&self, content: &str) -> String {
+        let content = if self.crlf {
+            content.replace('\n', "\r\n")
+        } else {
+            content.to_string()
+        };
+        if self.bom {
+            format!("{BOM}{content}")
+        } else {
+            content
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_lf() {
+        let text = NoteText::decode("- 09:00 朝\n".as_bytes()).unwrap();
+        assert_eq!(text.content, "- 09:00 朝\n");
+        assert_eq!(
+            text.encode("- 09:00 朝\n- 10:00 昼\n"),
+            "- 09:00 朝\n- 10:00 昼\n"
+        );
+    }
+
+    #[test]
+    fn test_decode_crlf_round_trip() {
+        let text = NoteText::decode("# Title\r\n- 09:00 朝\r\n".as_bytes()).unwrap();
+        assert_eq!(text.content, "# Title\n- 09:00 朝\n");
+        assert_eq!(
+            text.encode("# Title\n- 09:00 朝\n- 10:00 昼\n"),
+            "# Title\r\n- 09:00 朝\r\n- 10:00 昼\r\n"
+        );
+    }
+
+    #[test]
+    fn test_decode_bom_round_trip() {
+        let text = NoteText::decode("\u{feff}- 09:00 朝\r\n".as_bytes()).unwrap();
+        assert_eq!(text.content, "- 09:00 朝\n");
+        assert_eq!(
+            text.encode("- 09:00 朝\n- 10:00 昼\n"),
+            "\u{feff}- 09:00 朝\r\n- 10:00 昼\r\n"
+        );
+    }
+
+    #[test]
+    fn test_decode_mixed_line_endings_kept() {
+        let text = NoteText::decode("# Title\r\n- 09:00 朝\n".as_bytes()).unwrap();
+        assert_eq!(text.content, "# Title\r\n- 09:00 朝\n");
+        assert_eq!(
+            text.encode("# Title\r\n- 09:00 朝\n- 10:00 昼\n"),
+            "# Title\r\n- 09:00 朝\n- 10:00 昼\n"
+        );
+    }
+
+    #[test]
+    fn test_decode_empty() {
+        let text = NoteText::decode(b"").unwrap();
+        assert_eq!(text.content, "");
+        assert_eq!(text.encode("- 10:00 昼\n"), "- 10:00 昼\n");
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8() {
+        // Shift_JISの「メモ」
+        assert!(NoteText::decode(b"- 09:00 \x83\x81\x83\x82\r\n").is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! メモ行の読み取り
+//!
+//! ノート本文からThino形式のメモ（`- HH:MM 内容`）を読み取る。
+//! メモ行に続く空でない行は、複数行メモの続きとして同じメモに含める。
+//! 時刻は設定の時刻フォーマット（moment.js形式）で読み取り、一致しない場合は
+//! `H:MM`、`HH:MM`、`HH:MM:SS` 形式として読み取る。
+
+use chrono::NaiveTime;
+use serde::Serialize;
+
+use crate::insert;
+use crate::moment;
+
+/// タスク形式のメモ行のチェックボックス
+const CHECKBOXES: [&str; 3] = ["[ ] ", "[x] ", "[X] "];
+
+/// ノートに記録されたメモ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemoEntry {
+    /// 記録時刻（ノートに書かれた表記のまま、例: "09:15"、"9:15 PM"）
+    pub time: String,
+
+    /// メモの内容（複数行メモは改行で結合）
+    pub content: String,
+}
+
+/// ノート本文からメモを読み取る
+///
+/// フロントマターとコードブロック内のメモ行は対象外。メモ行の後は、空行・ヘッダー・
+/// 次のメモ行が現れるまでを同じメモの続きとして扱う。
+///
+/// # 引数
+///
+/// * `content` - ノート本文
+/// * `time_format` - メモの時刻フォーマット（moment.js形式、例: "HH:mm"）
+///
+/// # 戻り値
+///
+/// ノート内の出現順のメモ一覧
+pub fn parse_entries(content: &str, time_format: &str) -> Vec<MemoEntry> {
+    let lines: Vec<&str> = content.lines().collect();
+    let markdown = insert::markdown_lines(&lines);
+
+    let mut entries = Vec::new();
+    let mut current: Option<MemoEntry> = None;
+
+    for (line, is_markdown) in lines.iter().zip(markdown) {
+        if is_markdown && let Some((time, text)) = parse_memo_line(line, time_format) {
+            entries.extend(current.take());
+            current = Some(MemoEntry {
+                time: time.to_string(),
+                content: text.to_string(),
+            });
+            continue;
+        }
+
+        let ends_entry =
+            is_markdown && (line.trim().is_empty() || insert::heading_level(line).is_some());
+        if ends_entry {
+            entries.extend(current.take());
+        } else if let Some(entry) = current.as_mut() {
+            entry.content.push('\n');
+            entry.content.push_str(line);
+        }
+    }
+
+    entries.extend(current);
+    entries
+}
+
+/// メモ行の記録時刻を返す
+///
+/// メモ行でない場合や、時刻として解釈できない場合は `None`。
+pub(crate) fn memo_time(line: &str, time_format: &str) -> Option<NaiveTime> {
+    let (time, _) = parse_memo_line(line.trim_end_matches(['\r', '\n']), time_format)?;
+    if let Some((time, "")) = moment::parse_time_prefix(time_format, time) {
+        return Some(time);
+    }
+    NaiveTime::parse_from_str(time, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .ok()
+}
+
+/// メモ行を時刻と内容に分割する
+///
+/// `- {時刻} 内容` または `- [ ] {時刻} 内容` 形式の行のみ受け付ける。
+fn parse_memo_line<'a>(line: &'a str, time_format: &str) -> Option<(&'a str, &'a str)> {
+    let rest = line.strip_prefix("- ")?;
+    let rest = CHECKBOXES
+        .iter()
+        .find_map(|checkbox| rest.strip_prefix(checkbox))
+        .unwrap_or(rest);
+
+    if let Some((_, after)) = moment::parse_time_prefix(time_format, rest)
+        && (after.is_empty() || after.starts_with(' '))
+    {
+        let time = &rest[..rest.len() - after.len()];
+        return Some((time, after.strip_prefix(' ').unwrap_or(after)));
+    }
+
+    let (time, text) = rest.split_once(' ').unwrap_or((rest, ""));
+    is_time(time).then_some((time, text))
+}
+
+/// `H:MM`、`HH:MM`、`HH:MM:SS` 形式の時刻表記かどうかを判定
+fn is_time(text: &str) -> bool {
+    let mut parts = text.split(':');
+    let Some(hour) = parts.next() else {
+        return false;
+    };
+    let rest: Vec<&str> = parts.collect();
+
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    (1..=2).contains(&hour.len())
+        && is_digits(hour)
+        && (1..=2).contains(&rest.len())
+        && rest.iter().all(|part| part.len() == 2 && is_digits(part))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(time: &str, content: &str) -> MemoEntry {
+        MemoEntry {
+            time: time.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_entries_single_lines() {
+        let content = "# 2026-10-18\n- 09:15 朝のメモ\n- 12:00 昼のメモ\n";
+        assert_eq!(
+            parse_entries(content, "HH:mm"),
+            vec![entry("09:15", "朝のメモ"), entry("12:00", "昼のメモ")]
+        );
+    }
+
+    #[test]
+    fn test_parse_entries_multiline() {
+        let content = "- 09:15 TODO:\n- Task 1\n  続き\n- 10:00 次のメモ\n";
+        assert_eq!(
+            parse_entries(content, "HH:mm"),
+            vec![
+                entry("09:15", "TODO:\n- Task 1\n  続き"),
+                entry("10:00", "次のメモ")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_entries_ends_at_blank_line_and_heading() {
+        let content = "- 09:15 メモ\n\n普通の段落\n- 10:00 メモ2\n## Notes\n本文\n";
+        assert_eq!(
+            parse_entries(content, "HH:mm"),
+            vec![entry("09:15", "メモ"), entry("10:00", "メモ2")]
+        );
+    }
+
+    #[test]
+    fn test_parse_entries_task_prefix_and_seconds() {
+        let content = "- [ ] 09:15:30 タスク\n- [x] 10:00 完了\n";
+        assert_eq!(
+            parse_entries(content, "HH:mm"),
+            vec![entry("09:15:30", "タスク"), entry("10:00", "完了")]
+        );
+    }
+
+    #[test]
+    fn test_parse_entries_keeps_code_block_in_memo() {
+        let content = "- 09:15 コード\n```\n\n- 10:00 not a memo\n```\n";
+        assert_eq!(
+            parse_entries(content, "HH:mm"),
+            vec![entry("09:15", "コード\n```\n\n- 10:00 not a memo\n```")]
+        );
+    }
+
+    #[test]
+    fn test_parse_entries_ignores_frontmatter_and_plain_items() {
+        let content = "---\nlist:\n- 09:00 yaml\n---\n- 買い物\n- 24 items\n- 9:05 メモ\n";
+        assert_eq!(parse_entries(content, "HH:mm"), vec![entry("9:05", "メモ")]);
+    }
+
+    #[test]
+    fn test_memo_time() {
+        let time = |h, m, s| NaiveTime::from_hms_opt(h, m, s);
+        assert_eq!(memo_time("- 09:15 メモ\n", "HH:mm"), time(9, 15, 0));
+        assert_eq!(memo_time("- [ ] 9:05:30 タスク", "HH:mm"), time(9, 5, 30));
+        assert_eq!(memo_time("- 25:00 範囲外", "HH:mm"), None);
+        assert_eq!(memo_time("- 買い物", "HH:mm"), None);
+    }
+
+    #[test]
+    fn test_parse_entries_twelve_hour_format() {
+        // 設定の時刻フォーマットに一致しない行は従来の形式として読み取る
+        let content =
+            "- 9:05 AM 朝のメモ\n- [ ] 12:30 PM タスク\n- 21:00 以前のメモ\n- 9:05 AMとPM\n";
+        assert_eq!(
+            parse_entries(content, "h:mm A"),
+            vec![
+                entry("9:05 AM", "朝のメモ"),
+                entry("12:30 PM", "タスク"),
+                entry("21:00", "以前のメモ"),
+                entry("9:05", "AMとPM"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_memo_time_with_time_format() {
+        let time = |h, m, s| NaiveTime::from_hms_opt(h, m, s);
+        assert_eq!(memo_time("- 9:05 PM メモ", "h:mm A"), time(21, 5, 0));
+        assert_eq!(memo_time("- 12:05 am メモ", "hh:mm a"), time(0, 5, 0));
+        assert_eq!(memo_time("- 21:05:30 メモ", "HH:mm:ss"), time(21, 5, 30));
+        assert_eq!(memo_time("- 21:05 以前のメモ", "h:mm A"), time(21, 5, 0));
+    }
+
+    #[test]
+    fn test_is_time() {
+        assert!(is_time("09:15"));
+        assert!(is_time("9:15"));
+        assert!(is_time("09:15:30"));
+        assert!(!is_time("0915"));
+        assert!(!is_time("09:1"));
+        assert!(!is_time("ab:cd"));
+        assert!(!is_time("09:15:30:00"));
+    }
+}
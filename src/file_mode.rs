@@ -0,0 +1,138 @@
+//! FILEモードのメモ保存
+//!
+//! ThinoのFILEモードと同様に、1メモを1ファイルとして保存する。
+//! ファイル名は作成日時（`YYYYMMDDHHmmss.md`）で、フロントマターに作成・更新日時を持つ。
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+
+/// ファイル名に使用する日時フォーマット
+const FILE_NAME_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// フロントマターに記録する日時フォーマット
+const FRONTMATTER_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// メモファイルを作成する
+///
+/// 同じ秒に作成されたファイルが既に存在する場合は、`-1`、`-2` … の連番を付与する。
+///
+/// # 引数
+///
+/// * `vault_path` - Obsidian Vaultのパス
+/// * `folder` - メモを保存するフォルダ（Vaultルートからの相対パス）
+/// * `content` - メモの内容
+/// * `now` - 作成日時
+///
+/// # 戻り値
+///
+/// 作成したメモファイルのパス
+///
+/// # Errors
+///
+/// ディレクトリ作成やファイル書き込みに失敗した場合
+pub fn create_memo_file(
+    vault_path: &Path,
+    folder: &str,
+    content: &str,
+    now: NaiveDateTime,
+) -> io::Result<PathBuf> {
+    let dir = if folder.is_empty() {
+        vault_path.to_path_buf()
+    } else {
+        vault_path.join(folder)
+    };
+    fs::create_dir_all(&dir)?;
+
+    let stem = now.format(FILE_NAME_FORMAT).to_string();
+    let body = format_memo_file(content, now);
+
+    for suffix in 0.. {
+        let name = if suffix == 0 {
+            format!("{stem}.md")
+        } else {
+            format!("{stem}-{suffix}.md")
+        };
+        let path = dir.join(name);
+
+        // 既存ファイルを上書きしないよう新規作成のみ許可する
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(mut file) => {
+                file.write_all(body.as_bytes())?;
+                return Ok(path);
+            }
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+
+    unreachable!("suffix range is unbounded")
+}
+
+/// メモファイルの内容を生成する
+fn format_memo_file(content: &str, now: NaiveDateTime) -> String {
+    let date = now.format(FRONTMATTER_DATE_FORMAT);
+    format!("---\ncreatedAt: {date}\nupdatedAt: {date}\ntype: thino\n---\n{content}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use tempfile::tempdir;
+
+    fn test_datetime() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 1, 15)
+            .unwrap()
+            .and_hms_opt(14, 30, 5)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_format_memo_file() {
+        let result = format_memo_file("メモ", test_datetime());
+        assert_eq!(
+            result,
+            "---\ncreatedAt: 2026-01-15 14:30:05\nupdatedAt: 2026-01-15 14:30:05\ntype: thino\n---\nメモ\n"
+        );
+    }
+
+    #[test]
+    fn test_create_memo_file() {
+        let dir = tempdir().unwrap();
+
+        let path = create_memo_file(dir.path(), "Thino", "メモ", test_datetime()).unwrap();
+
+        assert_eq!(path, dir.path().join("Thino").join("20260115143005.md"));
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.ends_with("---\nメモ\n"));
+    }
+
+    #[test]
+    fn test_create_memo_file_without_folder() {
+        let dir = tempdir().unwrap();
+
+        let path = create_memo_file(dir.path(), "", "メモ", test_datetime()).unwrap();
+
+        assert_eq!(path, dir.path().join("20260115143005.md"));
+    }
+
+    #[test]
+    fn test_create_memo_file_same_second() {
+        let dir = tempdir().unwrap();
+
+        let first = create_memo_file(dir.path(), "", "メモ1", test_datetime()).unwrap();
+        let second = create_memo_file(dir.path(), "", "メモ2", test_datetime()).unwrap();
+
+        assert_eq!(second, dir.path().join("20260115143005-1.md"));
+        // 既存のファイルは上書きされない
+        assert!(fs::read_to_string(&first).unwrap().contains("メモ1"));
+        assert!(fs::read_to_string(&second).unwrap().contains("メモ2"));
+    }
+}
@@ -0,0 +1,89 @@
+//! メモ内容の取得
+//!
+//! コマンドライン引数または標準入力からメモ内容を組み立てる。
+
+use std::io::{self, IsTerminal, Read};
+
+/// 標準入力から読み込むことを示す引数
+const STDIN_ARG: &str = "-";
+
+/// メモ内容を取得する
+///
+/// 引数が `-` のみの場合、または引数がなく標準入力が端末でない場合
+/// （パイプやリダイレクト）は標準入力全体をメモ内容として読み込む。
+/// それ以外は引数をスペースで結合する。
+///
+/// # 引数
+///
+/// * `args` - CLIで渡されたメモ引数
+///
+/// # 戻り値
+///
+/// メモ内容。引数がなく標準入力が端末の場合は空文字列。
+///
+/// # Errors
+///
+/// 標準入力からの読み取りに失敗した場合
+pub fn read_memo_content(args: &[String]) -> io::Result<String> {
+    if let [arg] = args
+        && arg == STDIN_ARG
+    {
+        return read_content(io::stdin().lock());
+    }
+
+    if args.is_empty() {
+        let stdin = io::stdin();
+        // 端末からの入力待ちでブロックしないよう、パイプ時のみ読み込む
+        if stdin.is_terminal() {
+            return Ok(String::new());
+        }
+        return read_content(stdin.lock());
+    }
+
+    Ok(args.join(" "))
+}
+
+/// リーダーから全内容を読み込む
+///
+/// 複数行の内容はそのまま保持し、末尾の改行（`echo` 等が付与するもの）のみ取り除く。
+fn read_content(mut reader: impl Read) -> io::Result<String> {
+    let mut buf = String::new();
+    reader.read_to_string(&mut buf)?;
+    Ok(buf.trim_end_matches(['\n', '\r']).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_memo_content_joins_args() {
+        let args = vec!["Check".to_string(), "tomorrow".to_string()];
+        assert_eq!(read_memo_content(&args).unwrap(), "Check tomorrow");
+    }
+
+    #[test]
+    fn test_read_content_trims_trailing_newline() {
+        let result = read_content(Cursor::new("foo\n")).unwrap();
+        assert_eq!(result, "foo");
+    }
+
+    #[test]
+    fn test_read_content_trims_trailing_crlf() {
+        let result = read_content(Cursor::new("foo\r\n\r\n")).unwrap();
+        assert_eq!(result, "foo");
+    }
+
+    #[test]
+    fn test_read_content_preserves_multiline() {
+        let result = read_content(Cursor::new("TODO:\n- Task 1\n- Task 2\n")).unwrap();
+        assert_eq!(result, "TODO:\n- Task 1\n- Task 2");
+    }
+
+    #[test]
+    fn test_read_content_empty() {
+        let result = read_content(Cursor::new("")).unwrap();
+        assert_eq!(result, "");
+    }
+}
@@ -0,0 +1,773 @@
+//! メモ行の挿入
+//!
+//! ノート本文のどこにメモ行を挿入するかを決定し、挿入後の本文を生成する。
+//! 挿入先ヘッダーが指定されている場合はそのセクション内に挿入し、
+//! 挿入箇所以外の本文はバイト単位で変更しない。
+
+use std::ops::Range;
+
+use chrono::NaiveTime;
+
+use crate::entry;
+use crate::obsidian::InsertPosition;
+
+/// メモ行を挿入した本文を返す
+///
+/// `insert_after` が空の場合はファイル末尾に追記する。
+/// ヘッダーが見つかった場合は、`position` に従いセクション内の最後のリスト項目の直後
+/// （リスト項目がなければ最後の空でない行の直後）またはヘッダー直後に挿入する。
+/// ヘッダーが見つからない場合は、ファイル末尾にヘッダーを作成してその下に追記する。
+///
+/// フロントマターとコードブロック内の行はヘッダーとして扱わない。
+///
+/// # 引数
+///
+/// * `content` - 既存のノート本文
+/// * `memo_line` - 挿入するメモ行（末尾改行なし）
+/// * `insert_after` - 挿入先のヘッダー行（例: "## Journal"）
+/// * `position` - セクション内での挿入位置
+///
+/// # 戻り値
+///
+/// メモ行を挿入した本文
+pub fn insert_memo_line(
+    content: &str,
+    memo_line: &str,
+    insert_after: &str,
+    position: InsertPosition,
+) -> String {
+    let target = insert_after.trim();
+    if target.is_empty() {
+        return append_line(content, memo_line);
+    }
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let markdown = markdown_lines(&lines);
+    let target_level = heading_level(target);
+
+    let Some(heading_index) = (0..lines.len()).find(|&i| markdown[i] && lines[i].trim() == target)
+    else {
+        return append_with_heading(content, memo_line, target);
+    };
+
+    let index = match position {
+        InsertPosition::Prepend => heading_index,
+        InsertPosition::Append => {
+            let section =
+                heading_index + 1..section_end(&lines, &markdown, heading_index, target_level);
+            last_list_item_end(&lines, &markdown, section.clone())
+                .or_else(|| section.rev().find(|&i| !lines[i].trim().is_empty()))
+                .unwrap_or(heading_index)
+        }
+    };
+
+    insert_after_line(&lines, index, memo_line)
+}
+
+/// 記録時刻の順序を保つ位置にメモ行を挿入した本文を返す
+///
+/// 挿入先のセクション（`insert_after` が空の場合はノート全体）にあるメモ行を時刻順に並んで
+/// いるものとみなし、`position` が `Append` なら昇順、`Prepend` なら降順を保つ位置に挿入する。
+/// 順序の基準となるメモ行がない場合は `insert_memo_line` と同じ位置に挿入する。
+/// `day_start` より前の時刻は、深夜0時を過ぎた前日の続き（23:59より後）として並べる。
+///
+/// # 引数
+///
+/// * `content` - 既存のノート本文
+/// * `memo_line` - 挿入するメモ行（末尾改行なし）
+/// * `time` - メモの記録時刻
+/// * `time_format` - 既存のメモ行の時刻フォーマット（moment.js形式、例: "HH:mm"）
+/// * `day_start` - 1日の始まりの時刻（thn設定の `day_start`、未設定なら0時）
+/// * `insert_after` - 挿入先のヘッダー行（例: "## Journal"）
+/// * `position` - セクション内での並び順
+///
+/// # 戻り値
+///
+/// メモ行を挿入した本文
+pub fn insert_memo_line_ordered(
+    content: &str,
+    memo_line: &str,
+    time: NaiveTime,
+    time_format: &str,
+    day_start: NaiveTime,
+    insert_after: &str,
+    position: InsertPosition,
+) -> String {
+    // 1日の始まりからの経過時間で比較する
+    let since_day_start = |time: NaiveTime| time - (day_start - NaiveTime::MIN);
+    let time = since_day_start(time);
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let markdown = markdown_lines(&lines);
+
+    let target = insert_after.trim();
+    let section = if target.is_empty() {
+        0..lines.len()
+    } else {
+        let Some(heading_index) =
+            (0..lines.len()).find(|&i| markdown[i] && lines[i].trim() == target)
+        else {
+            return append_with_heading(content, memo_line, target);
+        };
+        heading_index + 1..section_end(&lines, &markdown, heading_index, heading_level(target))
+    };
+
+    let memos: Vec<(usize, NaiveTime)> = section
+        .clone()
+        .filter(|&i| markdown[i])
+        .filter_map(|i| {
+            entry::memo_time(lines[i], time_format).map(|memo_time| (i, since_day_start(memo_time)))
+        })
+        .collect();
+
+    let next = memos.iter().find(|(_, memo_time)| match position {
+        InsertPosition::Append => *memo_time > time,
+        InsertPosition::Prepend => *memo_time < time,
+    });
+
+    match (next, memos.last(), position) {
+        (Some(&(index, _)), _, _) => insert_before_line(&lines, index, memo_line),
+        (None, Some(&(index, _)), InsertPosition::Prepend) => {
+            let end = memo_end(&lines, &markdown, index, section.end, time_format);
+            insert_after_line(&lines, end, memo_line)
+        }
+        _ => insert_memo_line(content, memo_line, insert_after, position),
+    }
+}
+
+/// ファイル末尾にメモ行を追記する
+fn append_line(content: &str, memo_line: &str) -> String {
+    if content.is_empty() {
+        format!("{memo_line}\n")
+    } else if content.ends_with('\n') {
+        format!("{content}{memo_line}\n")
+    } else {
+        format!("{content}\n{memo_line}\n")
+    }
+}
+
+/// ファイル末尾にヘッダーを作成し、その下にメモ行を追記する
+///
+/// 既存の本文がある場合は、ヘッダーの前に空行を1行入れる。
+fn append_with_heading(content: &str, memo_line: &str, heading: &str) -> String {
+    let separator = if content.is_empty() || content.ends_with("\n\n") {
+        ""
+    } else if content.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+
+    format!("{content}{separator}{heading}\n{memo_line}\n")
+}
+
+/// 指定行の直後にメモ行を挿入する
+fn insert_after_line(lines: &[&str], index: usize, memo_line: &str) -> String {
+    let (head, tail) = lines.split_at(index + 1);
+    let head = head.concat();
+    let tail = tail.concat();
+
+    if head.ends_with('\n') {
+        format!("{head}{memo_line}\n{tail}")
+    } else {
+        format!("{head}\n{memo_line}\n{tail}")
+    }
+}
+
+/// 指定行の直前にメモ行を挿入する
+fn insert_before_line(lines: &[&str], index: usize, memo_line: &str) -> String {
+    let (head, tail) = lines.split_at(index);
+    format!("{}{memo_line}\n{}", head.concat(), tail.concat())
+}
+
+/// メモの最終行を返す
+///
+/// メモ行に続く、空行・ヘッダー・次のメモ行以外の行をメモの続きとして扱う。
+fn memo_end(
+    lines: &[&str],
+    markdown: &[bool],
+    start: usize,
+    section_end: usize,
+    time_format: &str,
+) -> usize {
+    (start + 1..section_end)
+        .take_while(|&i| {
+            !markdown[i]
+                || !(lines[i].trim().is_empty()
+                    || heading_level(lines[i]).is_some()
+                    || entry::memo_time(lines[i], time_format).is_some())
+        })
+        .last()
+        .unwrap_or(start)
+}
+
+/// 各行がMarkdown本文かどうかを返す
+///
+/// 先頭のフロントマター（`---` で囲まれた範囲）とフェンスドコードブロックは `false`。
+pub(crate) fn markdown_lines(lines: &[&str]) -> Vec<bool> {
+    let mut result = vec![true; lines.len()];
+
+    let mut start = 0;
+    if lines.first().is_some_and(|line| line.trim_end() == "---")
+        && let Some(end) = (1..lines.len()).find(|&i| lines[i].trim_end() == "---")
+    {
+        result[..=end].fill(false);
+        start = end + 1;
+    }
+
+    // 開いているフェンスの記号と長さ
+    let mut open_fence: Option<(char, usize)> = None;
+    for i in start..lines.len() {
+        let found = fence(lines[i]);
+        match open_fence {
+            None => {
+                if found.is_some() {
+                    open_fence = found;
+                    result[i] = false;
+                }
+            }
+            Some((marker, length)) => {
+                // 同じ記号で開始以上の長さの、情報文字列のない行で閉じる
+                if found.is_some_and(|(c, count)| c == marker && count >= length)
+                    && lines[i].trim().chars().all(|c| c == marker)
+                {
+                    open_fence = None;
+                }
+                result[i] = false;
+            }
+        }
+    }
+
+    result
+}
+
+/// コードフェンスの行であれば、フェンスの記号（`` ` `` または `~`）と長さを返す
+fn fence(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start();
+    let marker = trimmed.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let count = trimmed.chars().take_while(|&c| c == marker).count();
+    (count >= 3).then_some((marker, count))
+}
+
+/// セクションの終端（次の同レベル以上のヘッダーの行、またはファイル末尾）を返す
+///
+/// 挿入先がヘッダーでない場合は、次のヘッダーまでをセクションとみなす。
+fn section_end(
+    lines: &[&str],
+    markdown: &[bool],
+    heading_index: usize,
+    level: Option<usize>,
+) -> usize {
+    (heading_index + 1..lines.len())
+        .find(|&i| {
+            if !markdown[i] {
+                return false;
+            }
+            match (heading_level(lines[i]), level) {
+                (Some(found), Some(target)) => found <= target,
+                (Some(_), None) => true,
+                (None, _) => false,
+            }
+        })
+        .unwrap_or(lines.len())
+}
+
+/// セクション内の最後のリスト項目の最終行を返す
+///
+/// リスト項目に続く空でない行（インデントされた行や折り返し行）は項目の一部として扱う。
+fn last_list_item_end(lines: &[&str], markdown: &[bool], section: Range<usize>) -> Option<usize> {
+    let start = section
+        .clone()
+        .rev()
+        .find(|&i| markdown[i] && is_list_item(lines[i]))?;
+
+    let end = (start + 1..section.end)
+        .take_while(|&i| {
+            markdown[i] && !lines[i].trim().is_empty() && heading_level(lines[i]).is_none()
+        })
+        .last()
+        .unwrap_or(start);
+
+    Some(end)
+}
+
+/// リスト項目の行かどうかを判定
+///
+/// `- `、`* `、`+ `、`1. `、`1) ` で始まる行（インデント可）をリスト項目とみなす。
+/// `* * *` や `- - -` のような区切り線は除く。
+fn is_list_item(line: &str) -> bool {
+    if is_thematic_break(line) {
+        return false;
+    }
+
+    let trimmed = line.trim();
+    let rest = match trimmed.chars().next() {
+        Some('-' | '*' | '+') => &trimmed[1..],
+        Some(c) if c.is_ascii_digit() => {
+            let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+            match trimmed[digits..].strip_prefix(['.', ')']) {
+                Some(rest) => rest,
+                None => return false,
+            }
+        }
+        _ => return false,
+    };
+
+    rest.is_empty() || rest.starts_with(char::is_whitespace)
+}
+
+/// 区切り線（`-`、`*`、`_` のいずれか1種類を3つ以上並べ、空白のみを挟んだ行）かどうかを判定
+fn is_thematic_break(line: &str) -> bool {
+    let mut chars = line.trim().chars().filter(|c| !c.is_whitespace());
+    let Some(marker) = chars.next().filter(|c| matches!(c, '-' | '*' | '_')) else {
+        return false;
+    };
+    let mut count = 1;
+    for c in chars {
+        if c != marker {
+            return false;
+        }
+        count += 1;
+    }
+    count >= 3
+}
+
+/// Markdownヘッダーのレベルを返す
+///
+/// `#` の数（1〜6）の後に空白または行末が続く場合にレベルを返す。ヘッダーでなければ `None`。
+pub(crate) fn heading_level(line: &str) -> Option<usize> {
+    let line = line.trim_end();
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+
+    match line[level..].chars().next() {
+        None => Some(level),
+        Some(c) if c.is_whitespace() => Some(level),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMO: &str = "- 12:00 メモ";
+
+    // ========================================
+    // 末尾追記
+    // ========================================
+
+    #[test]
+    fn test_insert_without_target_appends_to_empty() {
+        let result = insert_memo_line("", MEMO, "", InsertPosition::Append);
+        assert_eq!(result, "- 12:00 メモ\n");
+    }
+
+    #[test]
+    fn test_insert_without_target_appends_to_end() {
+        let result = insert_memo_line("# Title\n", MEMO, "", InsertPosition::Append);
+        assert_eq!(result, "# Title\n- 12:00 メモ\n");
+    }
+
+    #[test]
+    fn test_insert_without_target_adds_missing_newline() {
+        let result = insert_memo_line("# Title", MEMO, "", InsertPosition::Append);
+        assert_eq!(result, "# Title\n- 12:00 メモ\n");
+    }
+
+    // ========================================
+    // ヘッダーの作成
+    // ========================================
+
+    #[test]
+    fn test_insert_target_not_found_creates_heading() {
+        let content = "# Title\n- 09:00 a\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(result, "# Title\n- 09:00 a\n\n## Journal\n- 12:00 メモ\n");
+    }
+
+    #[test]
+    fn test_insert_target_not_found_in_empty_note() {
+        let result = insert_memo_line("", MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(result, "## Journal\n- 12:00 メモ\n");
+    }
+
+    #[test]
+    fn test_insert_target_not_found_without_trailing_newline() {
+        let result = insert_memo_line("text", MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(result, "text\n\n## Journal\n- 12:00 メモ\n");
+    }
+
+    #[test]
+    fn test_insert_target_not_found_after_blank_line() {
+        let result = insert_memo_line("text\n\n", MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(result, "text\n\n## Journal\n- 12:00 メモ\n");
+    }
+
+    // ========================================
+    // ヘッダー配下への挿入
+    // ========================================
+
+    #[test]
+    fn test_insert_append_after_last_list_item() {
+        let content = "## Journal\n- 09:00 a\n- 10:00 b\n\n## Tasks\n- [ ] task\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "## Journal\n- 09:00 a\n- 10:00 b\n- 12:00 メモ\n\n## Tasks\n- [ ] task\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_append_before_trailing_paragraph() {
+        // リスト項目の後に段落がある場合はリストの末尾に挿入する
+        let content = "## Journal\n- 09:00 a\n\nSome notes.\n\n## Tasks\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "## Journal\n- 09:00 a\n- 12:00 メモ\n\nSome notes.\n\n## Tasks\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_append_after_multiline_item() {
+        // 複数行メモの継続行は項目の一部として扱う
+        let content = "## Journal\n- 09:00 TODO:\n  continued\nlazy line\n\n## Tasks\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "## Journal\n- 09:00 TODO:\n  continued\nlazy line\n- 12:00 メモ\n\n## Tasks\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_append_without_list_items() {
+        let content = "## Journal\nSome notes.\n\n## Tasks\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "## Journal\nSome notes.\n- 12:00 メモ\n\n## Tasks\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_append_empty_section() {
+        let content = "## Journal\n\n## Tasks\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(result, "## Journal\n- 12:00 メモ\n\n## Tasks\n");
+    }
+
+    #[test]
+    fn test_insert_append_last_section() {
+        let content = "# Title\n## Journal\n- 09:00 a";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(result, "# Title\n## Journal\n- 09:00 a\n- 12:00 メモ\n");
+    }
+
+    #[test]
+    fn test_insert_append_skips_lower_level_headers() {
+        // 下位レベルのヘッダーはセクションの一部として扱う
+        let content = "## Journal\n### Morning\n- 09:00 a\n## Tasks\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "## Journal\n### Morning\n- 09:00 a\n- 12:00 メモ\n## Tasks\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_append_stops_at_higher_level_header() {
+        let content = "## Journal\n- 09:00 a\n# Next\n- other\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "## Journal\n- 09:00 a\n- 12:00 メモ\n# Next\n- other\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_prepend_after_heading() {
+        let content = "## Journal\n- 09:00 a\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Prepend);
+        assert_eq!(result, "## Journal\n- 12:00 メモ\n- 09:00 a\n");
+    }
+
+    #[test]
+    fn test_insert_target_matches_trimmed_line() {
+        let content = "## Journal  \n- 09:00 a\n";
+        let result = insert_memo_line(content, MEMO, " ## Journal", InsertPosition::Append);
+        assert_eq!(result, "## Journal  \n- 09:00 a\n- 12:00 メモ\n");
+    }
+
+    #[test]
+    fn test_insert_preserves_rest_of_file() {
+        let content =
+            "---\ntags: [daily]\n---\n## Journal\n- 09:00 a\n\n## Notes\n\tindented  \n\n\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+
+        let (before, after) = result.split_once("- 12:00 メモ\n").unwrap();
+        assert_eq!(format!("{before}{after}"), content);
+    }
+
+    #[test]
+    fn test_insert_ignores_headings_in_code_block() {
+        let content = "```\n## Journal\n```\n## Journal\n- 09:00 a\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "```\n## Journal\n```\n## Journal\n- 09:00 a\n- 12:00 メモ\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_section_continues_past_code_block() {
+        let content = "## Journal\n- 09:00 a\n```\n# comment\n```\n- 10:00 b\n## Tasks\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "## Journal\n- 09:00 a\n```\n# comment\n```\n- 10:00 b\n- 12:00 メモ\n## Tasks\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_ignores_frontmatter_comment() {
+        let content = "---\n# Journal\n---\n# Journal\n- 09:00 a\n";
+        let result = insert_memo_line(content, MEMO, "# Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "---\n# Journal\n---\n# Journal\n- 09:00 a\n- 12:00 メモ\n"
+        );
+    }
+
+    // ========================================
+    // 時刻順の挿入
+    // ========================================
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_insert_ordered_between_memos() {
+        let content = "- 09:00 a\n続き\n- 13:00 b\n";
+        let result = insert_memo_line_ordered(
+            content,
+            MEMO,
+            time(12, 0),
+            "HH:mm",
+            NaiveTime::MIN,
+            "",
+            InsertPosition::Append,
+        );
+        assert_eq!(result, "- 09:00 a\n続き\n- 12:00 メモ\n- 13:00 b\n");
+    }
+
+    #[test]
+    fn test_insert_ordered_after_same_time() {
+        let content = "- 12:00 a\n- 13:00 b\n";
+        let result = insert_memo_line_ordered(
+            content,
+            MEMO,
+            time(12, 0),
+            "HH:mm",
+            NaiveTime::MIN,
+            "",
+            InsertPosition::Append,
+        );
+        assert_eq!(result, "- 12:00 a\n- 12:00 メモ\n- 13:00 b\n");
+    }
+
+    #[test]
+    fn test_insert_ordered_latest_uses_normal_position() {
+        let content = "# Journal\n- 09:00 a\n- 買い物\n\n# Notes\n- 20:00 other\n";
+        let result = insert_memo_line_ordered(
+            content,
+            MEMO,
+            time(12, 0),
+            "HH:mm",
+            NaiveTime::MIN,
+            "# Journal",
+            InsertPosition::Append,
+        );
+        assert_eq!(
+            result,
+            "# Journal\n- 09:00 a\n- 買い物\n- 12:00 メモ\n\n# Notes\n- 20:00 other\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_ordered_within_section() {
+        let content = "- 14:00 before\n## Journal\n- 09:00 a\n- 13:00 b\n";
+        let result = insert_memo_line_ordered(
+            content,
+            MEMO,
+            time(12, 0),
+            "HH:mm",
+            NaiveTime::MIN,
+            "## Journal",
+            InsertPosition::Append,
+        );
+        assert_eq!(
+            result,
+            "- 14:00 before\n## Journal\n- 09:00 a\n- 12:00 メモ\n- 13:00 b\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_ordered_prepend_descending() {
+        let content = "## Journal\n- 13:00 b\n- 09:00 a\n";
+        let result = insert_memo_line_ordered(
+            content,
+            MEMO,
+            time(12, 0),
+            "HH:mm",
+            NaiveTime::MIN,
+            "## Journal",
+            InsertPosition::Prepend,
+        );
+        assert_eq!(result, "## Journal\n- 13:00 b\n- 12:00 メモ\n- 09:00 a\n");
+    }
+
+    #[test]
+    fn test_insert_ordered_prepend_oldest() {
+        let content = "## Journal\n- 13:00 b\n  続き\n\n本文\n";
+        let result = insert_memo_line_ordered(
+            content,
+            MEMO,
+            time(12, 0),
+            "HH:mm",
+            NaiveTime::MIN,
+            "## Journal",
+            InsertPosition::Prepend,
+        );
+        assert_eq!(
+            result,
+            "## Journal\n- 13:00 b\n  続き\n- 12:00 メモ\n\n本文\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_ordered_creates_missing_heading() {
+        let result = insert_memo_line_ordered(
+            "- 13:00 b\n",
+            MEMO,
+            time(12, 0),
+            "HH:mm",
+            NaiveTime::MIN,
+            "## Journal",
+            InsertPosition::Append,
+        );
+        assert_eq!(result, "- 13:00 b\n\n## Journal\n- 12:00 メモ\n");
+    }
+
+    #[test]
+    fn test_insert_ordered_after_midnight_with_day_start() {
+        let content = "- 09:00 a\n- 23:10 b\n- 01:30 c\n- 03:00 d\n";
+        let result = insert_memo_line_ordered(
+            content,
+            "- 02:00 メモ",
+            time(2, 0),
+            "HH:mm",
+            time(4, 0),
+            "",
+            InsertPosition::Append,
+        );
+        assert_eq!(
+            result,
+            "- 09:00 a\n- 23:10 b\n- 01:30 c\n- 02:00 メモ\n- 03:00 d\n"
+        );
+
+        // 1日の始まり以降の時刻は0時台より前に並べる
+        let result = insert_memo_line_ordered(
+            content,
+            "- 22:00 メモ",
+            time(22, 0),
+            "HH:mm",
+            time(4, 0),
+            "",
+            InsertPosition::Append,
+        );
+        assert_eq!(
+            result,
+            "- 09:00 a\n- 22:00 メモ\n- 23:10 b\n- 01:30 c\n- 03:00 d\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_ordered_twelve_hour_format() {
+        let content = "- 9:00 AM a\n- 1:30 PM b\n";
+        let result = insert_memo_line_ordered(
+            content,
+            "- 12:00 PM メモ",
+            time(12, 0),
+            "h:mm A",
+            NaiveTime::MIN,
+            "",
+            InsertPosition::Append,
+        );
+        assert_eq!(result, "- 9:00 AM a\n- 12:00 PM メモ\n- 1:30 PM b\n");
+    }
+
+    // ========================================
+    // 行判定テスト
+    // ========================================
+
+    #[test]
+    fn test_heading_level() {
+        assert_eq!(heading_level("# Title"), Some(1));
+        assert_eq!(heading_level("### Sub\n"), Some(3));
+        assert_eq!(heading_level("##"), Some(2));
+        assert_eq!(heading_level("#tag"), None);
+        assert_eq!(heading_level("####### seven"), None);
+        assert_eq!(heading_level("- item"), None);
+    }
+
+    #[test]
+    fn test_markdown_lines_fence_closed_by_same_marker() {
+        // ``` で開いたフェンスは ~~~ や短いフェンスでは閉じない
+        let lines = ["````md\n", "~~~\n", "```\n", "- a\n", "````\n", "- b\n"];
+        assert_eq!(
+            markdown_lines(&lines),
+            vec![false, false, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn test_insert_after_list_not_thematic_break() {
+        let content = "## Journal\n- 09:00 a\n\n* * *\n\nfooter\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "## Journal\n- 09:00 a\n- 12:00 メモ\n\n* * *\n\nfooter\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_skips_list_in_mixed_fence() {
+        let content = "## Journal\n- 09:00 a\n```\n~~~\n- code\n```\n";
+        let result = insert_memo_line(content, MEMO, "## Journal", InsertPosition::Append);
+        assert_eq!(
+            result,
+            "## Journal\n- 09:00 a\n- 12:00 メモ\n```\n~~~\n- code\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_is_list_item() {
+        assert!(is_list_item("- 09:00 a\n"));
+        assert!(is_list_item("  * nested"));
+        assert!(is_list_item("+ plus"));
+        assert!(is_list_item("- [ ] task"));
+        assert!(is_list_item("1. first"));
+        assert!(is_list_item("10) tenth"));
+        assert!(is_list_item("-"));
+        assert!(!is_list_item("---"));
+        assert!(!is_list_item("* * *"));
+        assert!(!is_list_item("- - -"));
+        assert!(!is_list_item("  ___"));
+        assert!(!is_list_item("-text"));
+        assert!(!is_list_item("paragraph"));
+    }
+}
@@ -0,0 +1,47 @@
+//! thn - Obsidianのデイリーノートにメモを追記する
+//!
+//! Thino（obsidian-memos）プラグイン互換の形式でメモを保存する。
+//! `thn` コマンドと同じ処理をRustから直接呼び出せる。
+//!
+//! # 例
+//!
+//! ```
+//! use thn::Vault;
+//!
+//! # let dir = tempfile::tempdir().unwrap();
+//! # std::fs::create_dir(dir.path().join(".obsidian")).unwrap();
+//! let vault = Vault::open(dir.path())?;
+//! vault.append_memo("Summarize the meeting")?;
+//!
+//! let note = std::fs::read_to_string(vault.daily_note_path(vault.today()))?;
+//! assert!(note.contains("Summarize the meeting"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub mod clock;
+pub mod config;
+pub mod entry;
+pub mod list;
+pub mod memo;
+pub mod obsidian;
+pub mod search;
+
+mod atomic;
+mod canvas_mode;
+mod conflict;
+mod encoding;
+mod file_mode;
+mod insert;
+mod lock;
+mod moment;
+mod multi_mode;
+mod template;
+mod vault;
+
+pub use clock::{Clock, FixedClock, SystemClock, ZonedClock};
+pub use config::{Config, ConfigError};
+pub use list::{DailyMemo, MemoListing, SkippedNote};
+pub use memo::{MemoError, MemoWarning, append_memo, append_memo_at};
+pub use obsidian::DailyNotesSettings;
+pub use search::SearchQuery;
+pub use vault::Vault;
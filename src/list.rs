@@ -0,0 +1,329 @@
+//! デイリーノートのメモ一覧
+//!
+//! 指定した期間のデイリーノートを読み取り、記録されたメモを日付順に返す。
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::encoding::NoteText;
+use crate::entry::{self, MemoEntry};
+use crate::memo::{self, MemoError};
+use crate::obsidian;
+
+/// デイリーノートに記録されたメモ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyMemo {
+    /// デイリーノートの日付
+    pub date: NaiveDate,
+
+    /// 記録時刻（ノートに書かれた表記のまま）
+    pub time: String,
+
+    /// メモの内容
+    pub content: String,
+
+    /// デイリーノートのパス
+    pub path: PathBuf,
+}
+
+/// 読み取れずに読み飛ばしたノート
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct SkippedNote {
+    /// ノート（またはフォルダ）のパス
+    pub path: PathBuf,
+
+    /// 読み取れなかった理由
+    pub error: io::Error,
+}
+
+impl fmt::Display for SkippedNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "skipped {}: {}", self.path.display(), self.error)
+    }
+}
+
+/// メモの一覧と、読み飛ばしたノート
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct MemoListing {
+    /// 日付順・ノート内の出現順のメモ一覧
+    pub memos: Vec<DailyMemo>,
+
+    /// 読み取れずに読み飛ばしたノート
+    pub skipped: Vec<SkippedNote>,
+}
+
+/// 期間内のデイリーノートのメモを返す
+///
+/// デイリーノートが存在しない日は読み飛ばす。UTF-8として読めないなど、読み取れない
+/// ノートも読み飛ばす。メモの時刻はThinoの時刻フォーマットで読み取る。
+///
+/// 読み飛ばしたノートも必要な場合や、thn設定の `time_format` を使う場合は
+/// `list_memos_with_format`（または `Vault::list_memos_with_skipped`）を使う。
+///
+/// # 引数
+///
+/// * `vault_path` - Obsidian Vaultのパス
+/// * `from` - 期間の開始日
+/// * `to` - 期間の終了日（この日を含む）
+///
+/// # 戻り値
+///
+/// 日付順・ノート内の出現順のメモ一覧。`from` が `to` より後の場合は空。
+///
+/// # Errors
+///
+/// 読み取れないノートは読み飛ばすため、現在はエラーを返さない
+pub fn list_memos(
+    vault_path: &Path,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<DailyMemo>, MemoError> {
+    let thino = obsidian::load_thino_settings(vault_path);
+    list_memos_with_format(vault_path, &thino.time_format, from, to).map(|listing| listing.memos)
+}
+
+/// 時刻フォーマットを指定して期間内のデイリーノートのメモを返す
+///
+/// 読み取れないノートは読み飛ばし、戻り値の `skipped` に含める。
+///
+/// # 引数
+///
+/// * `vault_path` - Obsidian Vaultのパス
+/// * `time_format` - メモの時刻フォーマット（moment.js形式、例: "HH:mm"）
+/// * `from` - 期間の開始日
+/// * `to` - 期間の終了日（この日を含む）
+///
+/// # Errors
+///
+/// `list_memos` と同じ
+pub fn list_memos_with_format(
+    vault_path: &Path,
+    time_format: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<MemoListing, MemoError> {
+    let settings = obsidian::load_daily_notes_settings(vault_path);
+    let mut listing = MemoListing::default();
+
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        let path = memo::daily_note_path(vault_path, &settings, date);
+        listing.read_note(path, date, time_format);
+    }
+
+    Ok(listing)
+}
+
+impl MemoListing {
+    /// デイリーノートのメモを追加する（読み取れなければ読み飛ばしたノートに追加する）
+    pub(crate) fn read_note(&mut self, path: PathBuf, date: NaiveDate, time_format: &str) {
+        match read_daily_memos(&path, date, time_format) {
+            Ok(memos) => self.memos.extend(memos),
+            Err(error) => self.skipped.push(SkippedNote { path, error }),
+        }
+    }
+}
+
+/// デイリーノートのメモを読み取る
+///
+/// デイリーノートが存在しない場合は空の一覧を返す。
+///
+/// # Errors
+///
+/// デイリーノートの読み込みに失敗した場合や、UTF-8として読めない場合
+/// （`io::ErrorKind::InvalidData`）
+fn read_daily_memos(path: &Path, date: NaiveDate, time_format: &str) -> io::Result<Vec<DailyMemo>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let text = NoteText::decode(&bytes).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("not valid UTF-8: {err}"),
+        )
+    })?;
+
+    Ok(entry::parse_entries(&text.content, time_format)
+        .into_iter()
+        .map(|MemoEntry { time, content }| DailyMemo {
+            date,
+            time,
+            content,
+            path: path.to_path_buf(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn test_list_memos_range() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("2026-10-16.md"), "- 09:00 一日目\n").unwrap();
+        fs::write(
+            dir.path().join("2026-10-18.md"),
+            "- 08:00 三日目\n続き\n- 21:00 夜\n",
+        )
+        .unwrap();
+
+        let memos = list_memos(dir.path(), date(16), date(18)).unwrap();
+
+        let summary: Vec<(NaiveDate, &str, &str)> = memos
+            .iter()
+            .map(|memo| (memo.date, memo.time.as_str(), memo.content.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (date(16), "09:00", "一日目"),
+                (date(18), "08:00", "三日目\n続き"),
+                (date(18), "21:00", "夜"),
+            ]
+        );
+        assert_eq!(memos[0].path, dir.path().join("2026-10-16.md"));
+    }
+
+    #[test]
+    fn test_list_memos_uses_daily_notes_settings() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".obsidian")).unwrap();
+        fs::write(
+            dir.path().join(".obsidian").join("daily-notes.json"),
+            r#"{"folder": "Daily", "format": "DD.MM.YYYY"}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("Daily")).unwrap();
+        fs::write(
+            dir.path().join("Daily").join("17.10.2026.md"),
+            "- 10:00 メモ\n",
+        )
+        .unwrap();
+
+        let memos = list_memos(dir.path(), date(17), date(17)).unwrap();
+
+        assert_eq!(memos.len(), 1);
+        assert_eq!(memos[0].content, "メモ");
+    }
+
+    #[test]
+    fn test_list_memos_crlf_and_bom() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("2026-10-16.md"),
+            "\u{feff}- 09:00 朝\r\n続き\r\n- 21:00 夜\r\n",
+        )
+        .unwrap();
+
+        let memos = list_memos(dir.path(), date(16), date(16)).unwrap();
+
+        let summary: Vec<(&str, &str)> = memos
+            .iter()
+            .map(|memo| (memo.time.as_str(), memo.content.as_str()))
+            .collect();
+        assert_eq!(summary, vec![("09:00", "朝\n続き"), ("21:00", "夜")]);
+    }
+
+    #[test]
+    fn test_list_memos_skips_invalid_utf8() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("2026-10-16.md"), "- 09:00 一日目\n").unwrap();
+        // Shift_JISで保存されたノート
+        fs::write(
+            dir.path().join("2026-10-17.md"),
+            b"- 09:00 \x83\x81\x83\x82\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("2026-10-18.md"), "- 09:00 三日目\n").unwrap();
+
+        let listing = list_memos_with_format(dir.path(), "HH:mm", date(16), date(18)).unwrap();
+
+        let contents: Vec<&str> = listing
+            .memos
+            .iter()
+            .map(|memo| memo.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["一日目", "三日目"]);
+        assert_eq!(listing.skipped.len(), 1);
+        assert_eq!(listing.skipped[0].path, dir.path().join("2026-10-17.md"));
+        assert_eq!(listing.skipped[0].error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(list_memos(dir.path(), date(16), date(18)).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_list_memos_missing_notes() {
+        let dir = tempdir().unwrap();
+
+        let memos = list_memos(dir.path(), date(1), date(31)).unwrap();
+
+        assert!(memos.is_empty());
+    }
+
+    #[test]
+    fn test_list_memos_reversed_range() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("2026-10-16.md"), "- 09:00 メモ\n").unwrap();
+
+        let memos = list_memos(dir.path(), date(18), date(16)).unwrap();
+
+        assert!(memos.is_empty());
+    }
+
+    #[test]
+    fn test_list_memos_time_format() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".obsidian/plugins/obsidian-memos")).unwrap();
+        fs::write(
+            dir.path()
+                .join(".obsidian/plugins/obsidian-memos/data.json"),
+            r#"{"TimeFormat": "HH:mm:ss"}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("2026-10-16.md"),
+            "- 21:05:30 秒付き\n- 9:05 PM 夜\n",
+        )
+        .unwrap();
+        let summary = |memos: &[DailyMemo]| -> Vec<(String, String)> {
+            memos
+                .iter()
+                .map(|memo| (memo.time.clone(), memo.content.clone()))
+                .collect()
+        };
+
+        let memos = list_memos(dir.path(), date(16), date(16)).unwrap();
+        assert_eq!(
+            summary(&memos),
+            vec![
+                ("21:05:30".to_string(), "秒付き".to_string()),
+                ("9:05".to_string(), "PM 夜".to_string()),
+            ]
+        );
+
+        // 指定した時刻フォーマットで読み取る
+        let memos = list_memos_with_format(dir.path(), "h:mm A", date(16), date(16))
+            .unwrap()
+            .memos;
+        assert_eq!(
+            summary(&memos),
+            vec![
+                ("21:05:30".to_string(), "秒付き".to_string()),
+                ("9:05 PM".to_string(), "夜".to_string()),
+            ]
+        );
+    }
+}
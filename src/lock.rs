@@ -0,0 +1,166 @@
+//! ノート書き換え時の排他制御
+//!
+//! 複数の `thn` プロセスが同時に同じノートを読み込んで書き換えると、後から書き込んだ
+//! プロセスが先のメモを上書きしてしまう。ロックファイルのアドバイザリロックで、
+//! 読み込みから書き込みまでを直列化する。
+//!
+//! ノートは一時ファイルからのリネームで置き換えるため、ノート自体ではなく
+//! 置き換えられないロックファイルをロックする。ロックファイルは同期クライアントに
+//! コピーされないよう、Vaultの外（キャッシュディレクトリ）に作成する。
+
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 取得したロック（ドロップ時に解除される）
+#[derive(Debug)]
+pub(crate) struct NoteLock {
+    _file: File,
+}
+
+/// ノートを書き換える間の排他ロックを取得する
+///
+/// 同じノートを書き換える他のプロセスがロックを解除するまで待つ。
+/// ノートのディレクトリが存在しない場合は作成する。
+///
+/// # 引数
+///
+/// * `path` - 書き換えるノートのパス
+///
+/// # Errors
+///
+/// ディレクトリやロックファイルの作成、ロックの取得に失敗した場合
+pub(crate) fn lock(path: &Path) -> io::Result<NoteLock> {
+    let lock_dir = dirs::cache_dir()
+        .unwrap_or_else(env::temp_dir)
+        .join("thn")
+        .join("locks");
+    lock_in(&lock_dir, path)
+}
+
+/// ロックファイルのディレクトリを指定してロックを取得する
+fn lock_in(lock_dir: &Path, path: &Path) -> io::Result<NoteLock> {
+    let note = canonical_note_path(path)?;
+    fs::create_dir_all(lock_dir)?;
+
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_dir.join(lock_file_name(&note)))?;
+    file.lock()?;
+    Ok(NoteLock { _file: file })
+}
+
+/// ノートの正規化したパスを返す
+///
+/// 別のパスやシンボリックリンクから同じノートを書き換える場合も同じロックを使うため、
+/// ノートが存在すればノートを、存在しなければディレクトリを正規化する。
+fn canonical_note_path(path: &Path) -> io::Result<PathBuf> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir)?;
+
+    match fs::canonicalize(path) {
+        Ok(note) => Ok(note),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let dir = fs::canonicalize(dir)?;
+            Ok(match path.file_name() {
+                Some(name) => dir.join(name),
+                None => dir,
+            })
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// ノートのパスからロックファイル名を生成する
+///
+/// プロセスやビルドによらず同じ名前になるよう、FNV-1aでハッシュ化する。
+fn lock_file_name(note: &Path) -> String {
+    let hash = note
+        .as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    format!("{hash:016x}.lock")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_lock_keeps_lock_file_outside_vault() {
+        let vault = tempdir().unwrap();
+        let locks = tempdir().unwrap();
+        let path = vault.path().join("Daily").join("2026-01-15.md");
+
+        let _lock = lock_in(locks.path(), &path).unwrap();
+
+        // ノートのフォルダは作成するが、ロックファイルは置かない
+        assert!(vault.path().join("Daily").is_dir());
+        assert_eq!(fs::read_dir(vault.path().join("Daily")).unwrap().count(), 0);
+        assert_eq!(fs::read_dir(locks.path()).unwrap().count(), 1);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_lock_file_name_stable() {
+        let name = lock_file_name(Path::new("/vault/2026-01-15.md"));
+
+        assert_eq!(name, lock_file_name(Path::new("/vault/2026-01-15.md")));
+        assert_ne!(name, lock_file_name(Path::new("/vault/2026-01-16.md")));
+        assert!(name.ends_with(".lock"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lock_shared_through_symlink() {
+        let dir = tempdir().unwrap();
+        let locks = tempdir().unwrap();
+        let target = dir.path().join("shared.md");
+        fs::write(&target, "").unwrap();
+        let link = dir.path().join("2026-01-15.md");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        drop(lock_in(locks.path(), &link).unwrap());
+        drop(lock_in(locks.path(), &target).unwrap());
+
+        // リンクとリンク先は同じロックファイルを使う
+        assert_eq!(fs::read_dir(locks.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_lock_waits_for_release() {
+        let dir = tempdir().unwrap();
+        let locks = tempdir().unwrap();
+        let path = dir.path().join("2026-01-15.md");
+        let first = lock_in(locks.path(), &path).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let waiter = {
+            let path = path.clone();
+            let locks = locks.path().to_path_buf();
+            thread::spawn(move || {
+                let _lock = lock_in(&locks, &path).unwrap();
+                sender.send(()).unwrap();
+            })
+        };
+
+        // 先のロックを解除するまでは取得できない
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+        drop(first);
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        waiter.join().unwrap();
+    }
+}
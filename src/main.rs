@@ -1,33 +1,65 @@
 mod cli;
-mod config;
-mod memo;
-mod obsidian;
+mod editor;
+mod input;
 
-use std::path::PathBuf;
+use std::env;
+use std::path::{Path, PathBuf};
 
-use clap::Parser;
+use chrono::{NaiveDate, NaiveDateTime};
+use chrono_tz::Tz;
+use thn::clock::{self, Clock, FixedClock};
+use thn::{Config, DailyMemo, MemoListing, SearchQuery, config, list, memo, obsidian, search};
 
-use cli::Cli;
+use cli::{AddArgs, Command, ListArgs, SearchArgs};
+
+/// 設定の読み込みに関わるグローバルオプション
+struct ConfigOptions {
+    /// 名前付きVault（`--vault` または環境変数 `THN_VAULT`）
+    vault: Option<String>,
+
+    /// 記録日時に使うタイムゾーン（`--tz`）
+    timezone: Option<Tz>,
+}
 
 fn main() {
-    let cli = Cli::parse();
+    let cli = cli::parse();
+    let options = ConfigOptions {
+        vault: cli.vault.clone().or_else(config::vault_from_env),
+        timezone: cli.tz,
+    };
 
+    // 旧形式のオプション（`--init` / `--config`）
     if let Some(init_arg) = cli.init {
-        return run_init(init_arg);
+        return run_init(init_arg, cli.vault.as_deref());
     }
-
     if cli.config {
-        return run_config();
+        return run_config(&options);
     }
 
-    if cli.memo.is_empty() {
-        exit_with_error("memo content required");
+    match cli.command {
+        Some(Command::Init(args)) => {
+            let name = init_name(args.name.as_deref(), cli.vault.as_deref());
+            run_init(args.path, name);
+        }
+        Some(Command::Config) => run_config(&options),
+        Some(Command::Add(args)) => run_add(&args, &options),
+        Some(Command::List(args)) => run_list(&args, &options),
+        Some(Command::Search(args)) => run_search(&args, &options),
+        None => run_add(&AddArgs::default(), &options),
     }
+}
 
-    run_memo(&cli.memo);
+/// 登録する名前付きVaultの名前を返す（`--name`、なければ `--vault`）
+fn init_name<'a>(name: Option<&'a str>, vault: Option<&'a str>) -> Option<&'a str> {
+    match (name, vault) {
+        (Some(name), Some(vault)) if name != vault => exit_with_error(&format!(
+            "conflicting vault names: --name {name} and --vault {vault}"
+        )),
+        (name, vault) => name.or(vault),
+    }
 }
 
-fn run_init(init_arg: Option<PathBuf>) {
+fn run_init(init_arg: Option<PathBuf>, name: Option<&str>) {
     let vault_path = init_arg.unwrap_or_else(|| {
         config::prompt_vault_path().unwrap_or_else(|err| exit_with_error(&err.to_string()))
     });
@@ -36,27 +68,228 @@ fn run_init(init_arg: Option<PathBuf>) {
         exit_with_error(&err.to_string());
     }
 
-    let config = config::Config { vault_path };
-    if let Err(err) = config.save() {
+    // 既存の設定項目や他のVaultは保持したままVaultパスを更新する
+    let mut file =
+        config::load_file_or_default().unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    file.set_vault_path(name, vault_path);
+    if let Err(err) = file.save() {
         exit_with_error(&err.to_string());
     }
 }
 
-fn run_config() {
-    let config = config::load().unwrap_or_else(|err| exit_with_error(&err.to_string()));
+fn run_config(options: &ConfigOptions) {
+    let config = load_config(options);
     let daily = obsidian::load_daily_notes_settings(&config.vault_path);
+    let thino = obsidian::load_thino_settings(&config.vault_path);
 
     println!("vault_path: {}", config.vault_path.display());
     println!("daily_folder: {}", daily.folder);
     println!("daily_format: {}", daily.format);
+    println!(
+        "daily_note: {}",
+        memo::daily_note_path(&config.vault_path, &daily, today(&config)).display()
+    );
+    println!("insert_after: {}", memo::insert_target(&config, &thino));
+    println!("time_format: {}", memo::time_format(&config, &thino));
+    println!(
+        "day_start: {}",
+        config.day_start.unwrap_or_default().format("%H:%M")
+    );
+    println!(
+        "timezone: {}",
+        config.timezone.map_or("local", |timezone| timezone.name())
+    );
+    println!("save_mode: {}", memo::save_mode(&config, &thino).as_str());
+    println!(
+        "on_sync_conflict: {}",
+        config.on_sync_conflict.unwrap_or_default().as_str()
+    );
 }
 
-fn run_memo(args: &[String]) {
-    let memo_content = args.join(" ");
-    let config = config::load().unwrap_or_else(|err| exit_with_error(&err.to_string()));
+fn run_list(args: &ListArgs, options: &ConfigOptions) {
+    let config = load_config(options);
 
-    if let Err(err) = memo::append_memo(&config.vault_path, &memo_content) {
-        exit_with_error(&err.to_string());
+    let (from, to) = match args.date {
+        Some(date) => (date, date),
+        None => {
+            let to = args.to.unwrap_or_else(|| today(&config));
+            (args.from.unwrap_or(to), to)
+        }
+    };
+    if from > to {
+        exit_with_error("invalid date range: --from is after --to");
+    }
+
+    let thino = obsidian::load_thino_settings(&config.vault_path);
+    let time_format = memo::time_format(&config, &thino);
+    let listing = list::list_memos_with_format(&config.vault_path, time_format, from, to)
+        .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    let memos = report_skipped(listing);
+
+    if args.json {
+        print_json(&memos);
+    } else {
+        print!("{}", format_memo_list(&memos, from != to));
+    }
+}
+
+fn run_search(args: &SearchArgs, options: &ConfigOptions) {
+    let config = load_config(options);
+
+    let query = SearchQuery::new(
+        args.query.as_deref(),
+        args.regex,
+        args.ignore_case,
+        &args.tag,
+    )
+    .unwrap_or_else(|err| exit_with_error(&format!("invalid regex: {err}")));
+    let thino = obsidian::load_thino_settings(&config.vault_path);
+    let time_format = memo::time_format(&config, &thino);
+    let listing = search::search_memos_with_format(
+        &config.vault_path,
+        time_format,
+        &query,
+        args.from,
+        args.to,
+    )
+    .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    let memos = report_skipped(listing);
+
+    if args.json {
+        print_json(&memos);
+    } else {
+        print!("{}", format_search_results(&memos, &config.vault_path));
+    }
+}
+
+/// 読み飛ばしたノートを標準エラーに警告として表示し、メモ一覧を返す
+fn report_skipped(listing: MemoListing) -> Vec<DailyMemo> {
+    for skipped in &listing.skipped {
+        eprintln!("warning: {skipped}");
+    }
+    listing.memos
+}
+
+fn print_json(memos: &[DailyMemo]) {
+    let json =
+        serde_json::to_string_pretty(memos).unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    println!("{json}");
+}
+
+/// メモ一覧を表示用の文字列に変換する
+///
+/// 複数日を表示する場合は各行の先頭に日付を付ける。
+fn format_memo_list(memos: &[DailyMemo], with_date: bool) -> String {
+    memos
+        .iter()
+        .map(|memo| {
+            if with_date {
+                format!("{} {} {}\n", memo.date, memo.time, memo.content)
+            } else {
+                format!("{} {}\n", memo.time, memo.content)
+            }
+        })
+        .collect()
+}
+
+/// 検索結果を表示用の文字列に変換する
+///
+/// grepと同様に、各メモの先頭にVaultからの相対パスを付ける。
+fn format_search_results(memos: &[DailyMemo], vault_path: &Path) -> String {
+    memos
+        .iter()
+        .map(|memo| {
+            let path = memo.path.strip_prefix(vault_path).unwrap_or(&memo.path);
+            format!(
+                "{}: {} {} {}\n",
+                path.display(),
+                memo.date,
+                memo.time,
+                memo.content
+            )
+        })
+        .collect()
+}
+
+fn run_add(args: &AddArgs, options: &ConfigOptions) {
+    let memo_content = if args.edit {
+        editor::compose_memo(&args.memo.join(" "))
+            .unwrap_or_else(|err| exit_with_error(&err.to_string()))
+    } else {
+        input::read_memo_content(&args.memo).unwrap_or_else(|err| exit_with_error(&err.to_string()))
+    };
+
+    if memo_content.trim().is_empty() {
+        exit_with_error("memo content required");
+    }
+
+    run_memo(&memo_content, args, options);
+}
+
+/// `--at` / `--date` が指定された場合に記録日時を返す
+///
+/// 省略された方は現在の日付（`day_start` を考慮）・時刻で補う。
+fn memo_timestamp(args: &AddArgs, config: &Config, clock: &dyn Clock) -> Option<NaiveDateTime> {
+    if args.at.is_none() && args.date.is_none() {
+        return None;
+    }
+
+    let now = clock.now();
+    let today = memo::note_date(config, now);
+    let date = match args.date {
+        Some(date) => date
+            .resolve(today)
+            .unwrap_or_else(|| exit_with_error("invalid date: out of range")),
+        None => today,
+    };
+    Some(date.and_time(args.at.unwrap_or(now.time())))
+}
+
+fn run_memo(memo_content: &str, args: &AddArgs, options: &ConfigOptions) {
+    let config = load_config(options);
+    let clock = clock(&config);
+
+    let result = match memo_timestamp(args, &config, clock.as_ref()) {
+        Some(timestamp) => memo::append_memo_at(&config, memo_content, timestamp),
+        None => memo::append_memo(&config, memo_content, clock.as_ref()),
+    };
+    match result {
+        Ok(warnings) => {
+            for warning in warnings {
+                eprintln!("warning: {warning}");
+            }
+        }
+        Err(err) => exit_with_error(&err.to_string()),
+    }
+}
+
+/// `day_start` を考慮した今日の日付を返す
+fn today(config: &Config) -> NaiveDate {
+    memo::note_date(config, clock(config).now())
+}
+
+/// 選択されたVaultのthn設定を読み込み、`--tz` が指定されていればタイムゾーンを上書きする
+fn load_config(options: &ConfigOptions) -> Config {
+    let mut config = config::load_vault(options.vault.as_deref())
+        .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    if options.timezone.is_some() {
+        config.timezone = options.timezone;
+    }
+    config
+}
+
+/// 現在日時の取得元を返す
+///
+/// 環境変数 `THN_NOW` が空でなければその日時に固定する。それ以外は設定のタイムゾーンの
+/// システム時計を使う。
+fn clock(config: &Config) -> Box<dyn Clock> {
+    match env::var(clock::NOW_ENV) {
+        Ok(value) if !value.is_empty() => Box::new(
+            FixedClock::parse(&value, config.timezone).unwrap_or_else(|| {
+                exit_with_error(&format!("invalid {}: {value}", clock::NOW_ENV))
+            }),
+        ),
+        _ => clock::system_clock(config.timezone),
     }
 }
 
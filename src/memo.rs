@@ -7,14 +7,27 @@ use std::fmt;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-
-use chrono::Local;
-
-use crate::config;
-use crate::obsidian;
+use std::process::ExitStatus;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::atomic;
+use crate::canvas_mode;
+use crate::clock::Clock;
+use crate::config::{self, Config, SyncConflictPolicy};
+use crate::conflict;
+use crate::encoding::NoteText;
+use crate::file_mode;
+use crate::insert;
+use crate::lock;
+use crate::moment;
+use crate::multi_mode;
+use crate::obsidian::{self, DailyNotesSettings, InsertPosition, SaveMode, ThinoSettings};
+use crate::template;
 
 /// メモ操作に関するエラー
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum MemoError {
     /// 設定読み込みエラー
     ConfigError(config::ConfigError),
@@ -22,6 +35,18 @@ pub enum MemoError {
     Io(io::Error),
     /// ファイル書き込み失敗
     WriteFailed(PathBuf),
+    /// エディタが非ゼロで終了した
+    EditorFailed(String, ExitStatus),
+    /// メモ内容が空
+    EmptyMemo,
+    /// キャンバスファイルが不正
+    InvalidCanvas(PathBuf, serde_json::Error),
+    /// 書き込み先に同期クライアントの競合コピーがある
+    SyncConflict(PathBuf, Vec<PathBuf>),
+    /// ノートがUTF-8として読めない
+    InvalidEncoding(PathBuf, std::str::Utf8Error),
+    /// デイリーノートのテンプレートが読み込めない
+    InvalidTemplate(PathBuf, io::Error),
 }
 
 impl fmt::Display for MemoError {
@@ -30,6 +55,20 @@ impl fmt::Display for MemoError {
             MemoError::ConfigError(err) => write!(f, "{err}"),
             MemoError::Io(err) => write!(f, "{err}"),
             MemoError::WriteFailed(path) => write!(f, "failed to write: {}", path.display()),
+            MemoError::EditorFailed(editor, status) => {
+                write!(f, "editor failed ({status}): {editor}")
+            }
+            MemoError::EmptyMemo => write!(f, "aborting due to empty memo"),
+            MemoError::InvalidCanvas(path, err) => {
+                write!(f, "invalid canvas file: {}: {err}", path.display())
+            }
+            MemoError::InvalidEncoding(path, err) => {
+                write!(f, "note is not valid UTF-8: {}: {err}", path.display())
+            }
+            MemoError::InvalidTemplate(path, err) => {
+                write!(f, "failed to read template: {}: {err}", path.display())
+            }
+            MemoError::SyncConflict(path, copies) => fmt_sync_conflict(f, path, copies),
         }
     }
 }
@@ -39,11 +78,53 @@ impl std::error::Error for MemoError {
         match self {
             MemoError::ConfigError(err) => Some(err),
             MemoError::Io(err) => Some(err),
-            MemoError::WriteFailed(_) => None,
+            MemoError::InvalidCanvas(_, err) => Some(err),
+            MemoError::InvalidEncoding(_, err) => Some(err),
+            MemoError::InvalidTemplate(_, err) => Some(err),
+            MemoError::WriteFailed(_)
+            | MemoError::EditorFailed(..)
+            | MemoError::EmptyMemo
+            | MemoError::SyncConflict(..) => None,
         }
     }
 }
 
+/// メモの保存時の警告
+///
+/// メモは保存されたが、利用者に知らせるべき状態があったことを表す。
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MemoWarning {
+    /// 書き込み先に同期クライアントの競合コピーがある（`on_sync_conflict` が `warn` の場合）
+    SyncConflict(PathBuf, Vec<PathBuf>),
+}
+
+impl fmt::Display for MemoWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoWarning::SyncConflict(path, copies) => fmt_sync_conflict(f, path, copies),
+        }
+    }
+}
+
+/// 競合コピーの一覧をファイル名で表示する
+fn fmt_sync_conflict(f: &mut fmt::Formatter<'_>, path: &Path, copies: &[PathBuf]) -> fmt::Result {
+    let names: Vec<_> = copies
+        .iter()
+        .map(|copy| {
+            copy.file_name()
+                .unwrap_or(copy.as_os_str())
+                .to_string_lossy()
+        })
+        .collect();
+    write!(
+        f,
+        "sync conflict copies of {}: {}",
+        path.display(),
+        names.join(", ")
+    )
+}
+
 impl From<config::ConfigError> for MemoError {
     fn from(err: config::ConfigError) -> Self {
         MemoError::ConfigError(err)
@@ -58,20 +139,44 @@ impl From<io::Error> for MemoError {
 
 /// デイリーノートのパスを生成する
 ///
+/// ファイル名は `obsidian::format_date` でデイリーノートの日付フォーマットから生成する。
+/// メモの追記先と `thn --config` の表示はどちらもこの関数を使う。
+///
 /// # 引数
 ///
 /// * `vault_path` - Obsidian Vaultのパス
-/// * `folder` - デイリーノートを保存するフォルダ（Vaultルートからの相対パス）
-/// * `date_str` - 日付文字列（例: "2024-01-15"）
+/// * `settings` - デイリーノートプラグインの設定
+/// * `date` - 対象の日付
 ///
 /// # 戻り値
 ///
 /// デイリーノートのフルパス
-fn daily_note_path(vault_path: &Path, folder: &str, date_str: &str) -> PathBuf {
-    if folder.is_empty() {
-        vault_path.join(format!("{date_str}.md"))
+pub fn daily_note_path(
+    vault_path: &Path,
+    settings: &DailyNotesSettings,
+    date: NaiveDate,
+) -> PathBuf {
+    let file_name = format!("{}.md", obsidian::format_date(&settings.format, date));
+    if settings.folder.is_empty() {
+        vault_path.join(file_name)
     } else {
-        vault_path.join(folder).join(format!("{date_str}.md"))
+        vault_path.join(&settings.folder).join(file_name)
+    }
+}
+
+/// メモを保存するデイリーノートの日付を返す
+///
+/// thn設定の `day_start` より前の時刻は前日として扱う。深夜0時を過ぎてからのメモを
+/// 前日のデイリーノートにまとめるためのもの。
+///
+/// # 引数
+///
+/// * `config` - thn設定
+/// * `now` - 現在日時
+pub fn note_date(config: &Config, now: NaiveDateTime) -> NaiveDate {
+    match config.day_start {
+        Some(day_start) if now.time() < day_start => now.date().pred_opt().unwrap_or(now.date()),
+        _ => now.date(),
     }
 }
 
@@ -80,11 +185,12 @@ fn daily_note_path(vault_path: &Path, folder: &str, date_str: &str) -> PathBuf {
 /// # 引数
 ///
 /// * `path` - デイリーノートのパス
+/// * `initial_content` - 新しく作成する場合の内容
 ///
 /// # Errors
 ///
 /// ファイル作成やディレクトリ作成に失敗した場合
-fn ensure_daily_note(path: &Path) -> io::Result<()> {
+fn ensure_daily_note(path: &Path, initial_content: &str) -> io::Result<()> {
     if path.exists() {
         return Ok(());
     }
@@ -94,91 +200,376 @@ fn ensure_daily_note(path: &Path) -> io::Result<()> {
         fs::create_dir_all(parent)?;
     }
 
-    // 空ファイルを作成
-    fs::File::create(path)?;
-
-    Ok(())
+    // 書き込みの途中で中断されても、書きかけのノートを残さない
+    atomic::write(path, initial_content)
 }
 
 /// メモ行をフォーマットする
 ///
-/// Thino互換の形式でメモ行を生成する。
+/// Thino互換の形式でメモ行を生成する。接頭辞と本文の構成はThinoの設定に従う。
 ///
 /// # 引数
 ///
 /// * `content` - メモの内容
+/// * `thino` - Thinoの設定
+/// * `time_format` - moment.js形式の時刻フォーマット（例: "HH:mm"、"h:mm A"）
+/// * `timestamp` - メモの記録日時
 ///
 /// # 戻り値
 ///
-/// "- HH:MM {content}" 形式の文字列
-fn format_memo_line(content: &str) -> String {
-    let now = Local::now();
-    let time_str = now.format("%H:%M").to_string();
-    format!("- {time_str} {content}")
+/// デフォルト設定では "- HH:MM {content}" 形式の文字列
+fn format_memo_line(
+    content: &str,
+    thino: &ThinoSettings,
+    time_format: &str,
+    timestamp: NaiveDateTime,
+) -> String {
+    let time_str = moment::format_datetime(time_format, timestamp);
+    let body = thino
+        .default_memo_composition
+        .replace("{TIME}", &time_str)
+        .replace("{CONTENT}", content);
+    format!("{}{body}", thino.line_prefix())
+}
+
+/// メモの時刻フォーマットを返す
+///
+/// thn設定の `time_format` が指定されていればそれを、なければThinoの `TimeFormat` を返す。
+pub fn time_format<'a>(config: &'a Config, thino: &'a ThinoSettings) -> &'a str {
+    config.time_format.as_deref().unwrap_or(&thino.time_format)
+}
+
+/// メモの挿入先ヘッダーを返す
+///
+/// thn設定の `insert_after` が指定されていればそれを、なければThinoの `InsertAfter` を返す。
+pub fn insert_target<'a>(config: &'a Config, thino: &'a ThinoSettings) -> &'a str {
+    config
+        .insert_after
+        .as_deref()
+        .unwrap_or(&thino.insert_after)
+}
+
+/// メモの保存モードを返す
+///
+/// thn設定の `save_mode` が指定されていればそれを、なければThinoの保存モードを返す。
+pub fn save_mode(config: &Config, thino: &ThinoSettings) -> SaveMode {
+    config.save_mode.unwrap_or(thino.save_mode)
+}
+
+/// FILEモードの保存フォルダを返す
+///
+/// thn設定の `file_mode_folder` が指定されていればそれを、なければThinoの設定値を返す。
+pub fn file_mode_folder<'a>(config: &'a Config, thino: &'a ThinoSettings) -> &'a str {
+    config
+        .file_mode_folder
+        .as_deref()
+        .unwrap_or(&thino.file_mode_folder)
+}
+
+/// MULTIモードの保存ファイルを返す
+///
+/// thn設定の `multi_mode_file` が指定されていればそれを、なければThinoの設定値を返す。
+pub fn multi_mode_file<'a>(config: &'a Config, thino: &'a ThinoSettings) -> &'a str {
+    config
+        .multi_mode_file
+        .as_deref()
+        .unwrap_or(&thino.multi_mode_file)
+}
+
+/// CANVASモードの保存ファイルを返す
+///
+/// thn設定の `canvas_mode_file` が指定されていればそれを、なければThinoの設定値を返す。
+pub fn canvas_mode_file<'a>(config: &'a Config, thino: &'a ThinoSettings) -> &'a str {
+    config
+        .canvas_mode_file
+        .as_deref()
+        .unwrap_or(&thino.canvas_mode_file)
 }
 
-/// 日付フォーマットを変換する
+/// 書き込み先のノートの競合コピーを確認する
+///
+/// `on_sync_conflict` が `warn`（既定）の場合は警告を返して続行し、`refuse` の場合は
+/// エラーにする。`ignore` の場合は確認しない。`numbered` が `true` の場合は、
+/// iCloud Driveの番号付きのコピーも対象にする。
+///
+/// # Errors
 ///
-/// Obsidian形式（YYYY-MM-DD等）をchronoのstrftime形式に変換する。
+/// - `MemoError::SyncConflict` - `refuse` で競合コピーが見つかった場合
+/// - `MemoError::Io` - フォルダの読み込みに失敗した場合
+fn check_sync_conflicts(
+    config: &Config,
+    note_path: &Path,
+    numbered: bool,
+) -> Result<Vec<MemoWarning>, MemoError> {
+    let policy = config.on_sync_conflict.unwrap_or_default();
+    if policy == SyncConflictPolicy::Ignore {
+        return Ok(Vec::new());
+    }
+
+    let copies = conflict::find_conflict_copies(note_path, numbered)?;
+    if copies.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let path = note_path.to_path_buf();
+    match policy {
+        SyncConflictPolicy::Refuse => Err(MemoError::SyncConflict(path, copies)),
+        _ => Ok(vec![MemoWarning::SyncConflict(path, copies)]),
+    }
+}
+
+/// メモを保存する
+///
+/// 保存モードに応じて、デイリーノートへの追記、メモファイルの作成、
+/// MULTIモードのファイルへの追記、またはキャンバスへのノード追加を行う。
+/// 保存先の日付は `note_date` で決め、メモの時刻には実際の時刻を使う。
 ///
 /// # 引数
 ///
-/// * `format` - Obsidian形式の日付フォーマット
+/// * `config` - thn設定
+/// * `content` - 追記するメモの内容
+/// * `clock` - 記録日時を決める時計（通常は `SystemClock`）
+///
+/// # Errors
+///
+/// - `MemoError::ConfigError` - 設定読み込みに失敗した場合
+/// - `MemoError::Io` - ファイル操作に失敗した場合
+/// - `MemoError::WriteFailed` - ファイル書き込みに失敗した場合
+/// - `MemoError::InvalidCanvas` - キャンバスファイルのパースに失敗した場合
+/// - `MemoError::InvalidEncoding` - ノートがUTF-8として読めない場合
+/// - `MemoError::SyncConflict` - 書き込み先に競合コピーがあり、`on_sync_conflict` が `refuse` の場合
 ///
 /// # 戻り値
 ///
-/// chrono strftime形式のフォーマット文字列
-fn convert_date_format(format: &str) -> String {
-    format
-        .replace("YYYY", "%Y")
-        .replace("MM", "%m")
-        .replace("DD", "%d")
+/// 保存時の警告（`on_sync_conflict` が `warn` の場合の競合コピーなど）。警告がなければ空。
+pub fn append_memo(
+    config: &Config,
+    content: &str,
+    clock: &dyn Clock,
+) -> Result<Vec<MemoWarning>, MemoError> {
+    let now = clock.now();
+    save_memo(config, content, note_date(config, now), now, false)
 }
 
-/// メモをデイリーノートに追記する
+/// 記録日時を指定してメモを保存する
+///
+/// 過去の出来事を記録するためのもの。`timestamp` の日付のデイリーノート（MULTIモードでは
+/// 日付ヘッダー）に、既存のメモの記録時刻の順序を保つ位置で挿入する。
+/// FILEモードではファイル名と `createdAt` に、CANVASモードではメモ行の時刻に使う。
 ///
 /// # 引数
 ///
-/// * `vault_path` - Obsidian Vaultのパス
+/// * `config` - thn設定
 /// * `content` - 追記するメモの内容
+/// * `timestamp` - メモの記録日時
+///
+/// # Errors
+///
+/// `append_memo` と同じ
+pub fn append_memo_at(
+    config: &Config,
+    content: &str,
+    timestamp: NaiveDateTime,
+) -> Result<Vec<MemoWarning>, MemoError> {
+    save_memo(config, content, timestamp.date(), timestamp, true)
+}
+
+/// 保存モードに応じてメモを保存する
+///
+/// `date` はデイリーノートとMULTIモードの日付ヘッダーに使う日付。
+/// `ordered` が `true` の場合、デイリーノートとMULTIモードのファイルでは
+/// 記録時刻の順序を保つ位置に挿入する。
+fn save_memo(
+    config: &Config,
+    content: &str,
+    date: NaiveDate,
+    timestamp: NaiveDateTime,
+    ordered: bool,
+) -> Result<Vec<MemoWarning>, MemoError> {
+    let thino_settings = obsidian::load_thino_settings(&config.vault_path);
+
+    match save_mode(config, &thino_settings) {
+        SaveMode::Daily => {
+            append_to_daily_note(config, &thino_settings, content, date, timestamp, ordered)
+        }
+        SaveMode::File => {
+            let folder = file_mode_folder(config, &thino_settings);
+            file_mode::create_memo_file(&config.vault_path, folder, content, timestamp)?;
+            Ok(Vec::new())
+        }
+        SaveMode::Multi => {
+            append_to_multi_file(config, &thino_settings, content, date, timestamp, ordered)
+        }
+        SaveMode::Canvas => {
+            let file = canvas_mode_file(config, &thino_settings);
+            let canvas_path = canvas_mode::canvas_file_path(&config.vault_path, file);
+            let warnings = check_sync_conflicts(config, &canvas_path, false)?;
+            let time_format = time_format(config, &thino_settings);
+            let memo_line = format_memo_line(content, &thino_settings, time_format, timestamp);
+            canvas_mode::append_text_node(&canvas_path, &memo_line)?;
+            Ok(warnings)
+        }
+    }
+}
+
+/// メモをデイリーノートに追記する
 ///
 /// # Errors
 ///
-/// - `MemoError::ConfigError` - 設定読み込みに失敗した場合
 /// - `MemoError::Io` - ファイル操作に失敗した場合
+/// - `MemoError::InvalidEncoding` - ノートがUTF-8として読めない場合
+/// - `MemoError::InvalidTemplate` - テンプレートが読み込めない場合
 /// - `MemoError::WriteFailed` - ファイル書き込みに失敗した場合
-pub fn append_memo(vault_path: &Path, content: &str) -> Result<(), MemoError> {
+/// - `MemoError::SyncConflict` - 競合コピーがあり、書き込みを拒否する設定の場合
+fn append_to_daily_note(
+    config: &Config,
+    thino_settings: &ThinoSettings,
+    content: &str,
+    date: NaiveDate,
+    timestamp: NaiveDateTime,
+    ordered: bool,
+) -> Result<Vec<MemoWarning>, MemoError> {
+    let vault_path = config.vault_path.as_path();
+
     // Obsidian設定を読み込む
     let daily_notes_settings = obsidian::load_daily_notes_settings(vault_path);
 
-    // 今日の日付を取得
-    let today = Local::now();
-    let chrono_format = convert_date_format(&daily_notes_settings.format);
-    let date_str = today.format(&chrono_format).to_string();
+    // 保存先の日付のデイリーノートのパスを生成
+    let note_path = daily_note_path(vault_path, &daily_notes_settings, date);
+    let warnings =
+        check_sync_conflicts(config, &note_path, config.icloud_conflicts.unwrap_or(false))?;
 
-    // デイリーノートのパスを生成
-    let note_path = daily_note_path(vault_path, &daily_notes_settings.folder, &date_str);
+    // 新しく作成する場合はデイリーノートのテンプレートを展開する
+    let initial_content = if note_path.exists() {
+        String::new()
+    } else {
+        let title = note_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy())
+            .unwrap_or_default();
+        template::new_daily_note(
+            vault_path,
+            &daily_notes_settings,
+            date.and_time(timestamp.time()),
+            &title,
+        )?
+    };
 
-    // デイリーノートが存在しなければ作成
-    ensure_daily_note(&note_path)?;
+    // メモ行を生成
+    let time_format = time_format(config, thino_settings);
+    let memo_line = format_memo_line(content, thino_settings, time_format, timestamp);
+
+    // 挿入先ヘッダーの配下に挿入
+    insert_into_note(
+        &note_path,
+        &initial_content,
+        &memo_line,
+        insert_target(config, thino_settings),
+        thino_settings.insert_position,
+        ordered.then(|| {
+            (
+                timestamp.time(),
+                time_format,
+                config.day_start.unwrap_or_default(),
+            )
+        }),
+    )?;
+    Ok(warnings)
+}
 
-    // 既存のコンテンツを読み込む
-    let existing_content = fs::read_to_string(&note_path).unwrap_or_default();
+/// メモをMULTIモードのファイルに追記する
+///
+/// 保存先の日付ヘッダーの配下に挿入する。ヘッダーがなければファイル末尾に作成する。
+///
+/// # Errors
+///
+/// - `MemoError::Io` - ファイル操作に失敗した場合
+/// - `MemoError::InvalidEncoding` - ノートがUTF-8として読めない場合
+/// - `MemoError::WriteFailed` - ファイル書き込みに失敗した場合
+/// - `MemoError::SyncConflict` - 競合コピーがあり、書き込みを拒否する設定の場合
+fn append_to_multi_file(
+    config: &Config,
+    thino_settings: &ThinoSettings,
+    content: &str,
+    date: NaiveDate,
+    timestamp: NaiveDateTime,
+    ordered: bool,
+) -> Result<Vec<MemoWarning>, MemoError> {
+    let vault_path = config.vault_path.as_path();
+    let daily_notes_settings = obsidian::load_daily_notes_settings(vault_path);
 
-    // メモ行を生成
-    let memo_line = format_memo_line(content);
+    let note_path =
+        multi_mode::multi_file_path(vault_path, multi_mode_file(config, thino_settings));
+    let warnings = check_sync_conflicts(config, &note_path, false)?;
+    let heading = multi_mode::date_heading(&daily_notes_settings.format, date);
+    let time_format = time_format(config, thino_settings);
+    let memo_line = format_memo_line(content, thino_settings, time_format, timestamp);
+
+    insert_into_note(
+        &note_path,
+        "",
+        &memo_line,
+        &heading,
+        thino_settings.insert_position,
+        ordered.then(|| {
+            (
+                timestamp.time(),
+                time_format,
+                config.day_start.unwrap_or_default(),
+            )
+        }),
+    )?;
+    Ok(warnings)
+}
 
-    // ファイル末尾に追記
-    let new_content = if existing_content.is_empty() {
-        format!("{memo_line}\n")
-    } else if existing_content.ends_with('\n') {
-        format!("{existing_content}{memo_line}\n")
-    } else {
-        format!("{existing_content}\n{memo_line}\n")
+/// ノートの指定ヘッダー配下にメモ行を挿入して保存する
+///
+/// ノートが存在しなければ `initial_content` の内容で作成する。`ordered_by` に記録時刻と
+/// 時刻フォーマット、1日の始まりの時刻が指定された場合は、既存のメモの記録時刻の順序を
+/// 保つ位置に挿入する。
+/// 読み込みから書き込みまでは他の `thn` プロセスと排他する。ノートの改行コード（CRLF）と
+/// BOMは保持する。
+///
+/// # Errors
+///
+/// - `MemoError::Io` - ファイル操作に失敗した場合
+/// - `MemoError::InvalidEncoding` - ノートがUTF-8として読めない場合
+/// - `MemoError::WriteFailed` - ファイル書き込みに失敗した場合
+fn insert_into_note(
+    note_path: &Path,
+    initial_content: &str,
+    memo_line: &str,
+    insert_after: &str,
+    position: InsertPosition,
+    ordered_by: Option<(NaiveTime, &str, NaiveTime)>,
+) -> Result<(), MemoError> {
+    // 同時に実行された他のthnと読み込みから書き込みまでが重ならないようにする
+    let _lock = lock::lock(note_path)?;
+
+    // ノートが存在しなければ作成
+    ensure_daily_note(note_path, initial_content)?;
+
+    // 既存のコンテンツを読み込む（読めないノートは上書きしない）
+    let existing = NoteText::decode(&fs::read(note_path)?)
+        .map_err(|err| MemoError::InvalidEncoding(note_path.to_path_buf(), err))?;
+    let existing_content = existing.content.as_str();
+
+    let new_content = match ordered_by {
+        Some((time, time_format, day_start)) => insert::insert_memo_line_ordered(
+            existing_content,
+            memo_line,
+            time,
+            time_format,
+            day_start,
+            insert_after,
+            position,
+        ),
+        None => insert::insert_memo_line(existing_content, memo_line, insert_after, position),
     };
 
-    // ファイルに書き込む
-    fs::write(&note_path, new_content).map_err(|_| MemoError::WriteFailed(note_path.clone()))?;
+    // 一時ファイル経由で置き換え、書き込みに失敗しても元のノートを残す
+    atomic::write(note_path, existing.encode(&new_content))
+        .map_err(|_| MemoError::WriteFailed(note_path.to_path_buf()))?;
 
     Ok(())
 }
@@ -186,20 +577,46 @@ pub fn append_memo(vault_path: &Path, content: &str) -> Result<(), MemoError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::FixedClock;
     use std::fs;
     use tempfile::tempdir;
 
+    /// テスト用のthn設定を作成するヘルパー関数
+    fn test_config(vault_path: &Path) -> Config {
+        Config {
+            vault_path: vault_path.to_path_buf(),
+            ..Config::default()
+        }
+    }
+
     // ========================================
     // タスク8: daily_note_path テスト
     // ========================================
 
+    /// テスト用のデイリーノート設定を作成するヘルパー関数
+    fn daily_settings(folder: &str, format: &str) -> DailyNotesSettings {
+        DailyNotesSettings {
+            folder: folder.to_string(),
+            format: format.to_string(),
+            template: String::new(),
+        }
+    }
+
+    fn test_date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+    }
+
+    /// `test_date()` の 14:30 を返す時計
+    fn test_clock() -> FixedClock {
+        FixedClock(test_date().and_hms_opt(14, 30, 0).unwrap())
+    }
+
     #[test]
     fn test_daily_note_path_with_folder() {
         let vault_path = Path::new("/vault");
-        let folder = "Daily";
-        let date_str = "2024-01-15";
+        let settings = daily_settings("Daily", "YYYY-MM-DD");
 
-        let result = daily_note_path(vault_path, folder, date_str);
+        let result = daily_note_path(vault_path, &settings, test_date());
 
         assert_eq!(result, PathBuf::from("/vault/Daily/2024-01-15.md"));
     }
@@ -207,10 +624,9 @@ mod tests {
     #[test]
     fn test_daily_note_path_without_folder() {
         let vault_path = Path::new("/vault");
-        let folder = "";
-        let date_str = "2024-01-15";
+        let settings = daily_settings("", "YYYY-MM-DD");
 
-        let result = daily_note_path(vault_path, folder, date_str);
+        let result = daily_note_path(vault_path, &settings, test_date());
 
         assert_eq!(result, PathBuf::from("/vault/2024-01-15.md"));
     }
@@ -218,10 +634,9 @@ mod tests {
     #[test]
     fn test_daily_note_path_nested_folder() {
         let vault_path = Path::new("/vault");
-        let folder = "Notes/Daily";
-        let date_str = "2024-01-15";
+        let settings = daily_settings("Notes/Daily", "YYYY-MM-DD");
 
-        let result = daily_note_path(vault_path, folder, date_str);
+        let result = daily_note_path(vault_path, &settings, test_date());
 
         assert_eq!(result, PathBuf::from("/vault/Notes/Daily/2024-01-15.md"));
     }
@@ -229,14 +644,24 @@ mod tests {
     #[test]
     fn test_daily_note_path_slash_format() {
         let vault_path = Path::new("/vault");
-        let folder = "";
-        let date_str = "2024/01/15";
+        let settings = daily_settings("", "YYYY/MM/DD");
 
-        let result = daily_note_path(vault_path, folder, date_str);
+        let result = daily_note_path(vault_path, &settings, test_date());
 
         assert_eq!(result, PathBuf::from("/vault/2024/01/15.md"));
     }
 
+    #[test]
+    fn test_daily_note_path_moment_tokens() {
+        // 曜日名や月名を含むフォーマットもObsidianと同じファイル名になる
+        let vault_path = Path::new("/vault");
+        let settings = daily_settings("Daily", "YYYY-MM-DD ddd");
+
+        let result = daily_note_path(vault_path, &settings, test_date());
+
+        assert_eq!(result, PathBuf::from("/vault/Daily/2024-01-15 Mon.md"));
+    }
+
     // ========================================
     // タスク9: ensure_daily_note テスト
     // ========================================
@@ -246,7 +671,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let note_path = dir.path().join("2024-01-15.md");
 
-        ensure_daily_note(&note_path).unwrap();
+        ensure_daily_note(&note_path, "").unwrap();
 
         assert!(note_path.exists());
         let content = fs::read_to_string(&note_path).unwrap();
@@ -258,7 +683,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let note_path = dir.path().join("Daily").join("2024").join("2024-01-15.md");
 
-        ensure_daily_note(&note_path).unwrap();
+        ensure_daily_note(&note_path, "").unwrap();
 
         assert!(note_path.exists());
         let content = fs::read_to_string(&note_path).unwrap();
@@ -273,7 +698,7 @@ mod tests {
         // 既存のファイルを作成
         fs::write(&note_path, "existing content").unwrap();
 
-        ensure_daily_note(&note_path).unwrap();
+        ensure_daily_note(&note_path, "").unwrap();
 
         // 既存のコンテンツが保持されている
         let content = fs::read_to_string(&note_path).unwrap();
@@ -284,52 +709,85 @@ mod tests {
     // タスク11: format_memo_line テスト
     // ========================================
 
+    fn test_time() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(21, 5, 30)
+            .unwrap()
+    }
+
     #[test]
     fn test_format_memo_line_format() {
-        let content = "テストメモ";
-        let result = format_memo_line(content);
-
-        // "- HH:MM テストメモ" 形式をチェック
-        assert!(result.starts_with("- "));
-        assert!(result.ends_with(" テストメモ"));
-
-        // 時刻部分のフォーマットをチェック（HH:MM）
-        let time_part = &result[2..7];
-        assert!(time_part.chars().nth(2) == Some(':'));
-        assert!(time_part[..2].parse::<u32>().is_ok());
-        assert!(time_part[3..].parse::<u32>().is_ok());
+        let result = format_memo_line(
+            "テストメモ",
+            &ThinoSettings::default(),
+            "HH:mm",
+            test_time(),
+        );
+
+        assert_eq!(result, "- 21:05 テストメモ");
     }
 
     #[test]
     fn test_format_memo_line_with_special_chars() {
         let content = "メモ with special chars: @#$%";
-        let result = format_memo_line(content);
+        let result = format_memo_line(content, &ThinoSettings::default(), "HH:mm", test_time());
 
         assert!(result.contains("メモ with special chars: @#$%"));
     }
 
-    // ========================================
-    // convert_date_format テスト
-    // ========================================
-
     #[test]
-    fn test_convert_date_format_standard() {
-        assert_eq!(convert_date_format("YYYY-MM-DD"), "%Y-%m-%d");
+    fn test_format_memo_line_task_prefix() {
+        let thino = ThinoSettings {
+            default_prefix: "Task".to_string(),
+            ..ThinoSettings::default()
+        };
+        let result = format_memo_line("タスク", &thino, "HH:mm", test_time());
+
+        assert_eq!(result, "- [ ] 21:05 タスク");
     }
 
     #[test]
-    fn test_convert_date_format_slash() {
-        assert_eq!(convert_date_format("YYYY/MM/DD"), "%Y/%m/%d");
+    fn test_format_memo_line_composition() {
+        let thino = ThinoSettings {
+            default_memo_composition: "{CONTENT} ({TIME})".to_string(),
+            ..ThinoSettings::default()
+        };
+        let result = format_memo_line("メモ {TIME}", &thino, "HH:mm:ss", test_time());
+
+        // 内容中の "{TIME}" は置換されない
+        assert_eq!(result, "- メモ {TIME} (21:05:30)");
     }
 
     #[test]
-    fn test_convert_date_format_compact() {
-        assert_eq!(convert_date_format("YYYYMMDD"), "%Y%m%d");
+    fn test_format_memo_line_time_formats() {
+        let thino = ThinoSettings::default();
+        let cases = [
+            ("HH:mm:ss", "- 21:05:30 メモ"),
+            ("h:mm A", "- 9:05 PM メモ"),
+            ("hh:mm a", "- 09:05 pm メモ"),
+            ("HH%mm", "- 21%05 メモ"),
+        ];
+
+        for (time_format, expected) in cases {
+            assert_eq!(
+                format_memo_line("メモ", &thino, time_format, test_time()),
+                expected
+            );
+        }
     }
 
     #[test]
-    fn test_convert_date_format_reversed() {
-        assert_eq!(convert_date_format("DD-MM-YYYY"), "%d-%m-%Y");
+    fn test_time_format_prefers_config() {
+        let thino = ThinoSettings {
+            time_format: "HH:mm:ss".to_string(),
+            ..ThinoSettings::default()
+        };
+        let mut config = Config::default();
+        assert_eq!(time_format(&config, &thino), "HH:mm:ss");
+
+        config.time_format = Some("h:mm A".to_string());
+        assert_eq!(time_format(&config, &thino), "h:mm A");
     }
 
     // ========================================
@@ -342,6 +800,27 @@ mod tests {
         assert_eq!(err.to_string(), "failed to write: /path/to/file.md");
     }
 
+    #[test]
+    fn test_memo_error_display_empty_memo() {
+        let err = MemoError::EmptyMemo;
+        assert_eq!(err.to_string(), "aborting due to empty memo");
+    }
+
+    #[test]
+    fn test_memo_error_display_sync_conflict() {
+        let err = MemoError::SyncConflict(
+            PathBuf::from("/vault/2026-01-15.md"),
+            vec![
+                PathBuf::from("/vault/2026-01-15 (conflict).md"),
+                PathBuf::from("/vault/2026-01-15 2.md"),
+            ],
+        );
+        assert_eq!(
+            err.to_string(),
+            "sync conflict copies of /vault/2026-01-15.md: 2026-01-15 (conflict).md, 2026-01-15 2.md"
+        );
+    }
+
     #[test]
     fn test_memo_error_from_io_error() {
         let io_err = io::Error::new(io::ErrorKind::PermissionDenied, "permission denied");
@@ -376,15 +855,12 @@ mod tests {
         )
         .unwrap();
 
-        append_memo(vault_path, "テストメモ").unwrap();
-
-        // 今日の日付でファイルが作成されていることを確認
-        let today = Local::now().format("%Y-%m-%d").to_string();
-        let note_path = vault_path.join(format!("{today}.md"));
-        assert!(note_path.exists());
+        append_memo(&test_config(vault_path), "テストメモ", &test_clock()).unwrap();
 
+        // 時計の日付でファイルが作成されていることを確認
+        let note_path = vault_path.join("2024-01-15.md");
         let content = fs::read_to_string(&note_path).unwrap();
-        assert!(content.contains("テストメモ"));
+        assert_eq!(content, "- 14:30 テストメモ\n");
     }
 
     #[test]
@@ -402,11 +878,10 @@ mod tests {
         .unwrap();
 
         // 既存のデイリーノートを作成
-        let today = Local::now().format("%Y-%m-%d").to_string();
-        let note_path = vault_path.join(format!("{today}.md"));
+        let note_path = vault_path.join("2024-01-15.md");
         fs::write(&note_path, "# Existing content\n").unwrap();
 
-        append_memo(vault_path, "新しいメモ").unwrap();
+        append_memo(&test_config(vault_path), "新しいメモ", &test_clock()).unwrap();
 
         let content = fs::read_to_string(&note_path).unwrap();
         assert!(content.contains("# Existing content"));
@@ -427,16 +902,189 @@ mod tests {
         )
         .unwrap();
 
-        append_memo(vault_path, "フォルダ内メモ").unwrap();
+        append_memo(&test_config(vault_path), "フォルダ内メモ", &test_clock()).unwrap();
 
-        let today = Local::now().format("%Y-%m-%d").to_string();
-        let note_path = vault_path.join("Daily").join(format!("{today}.md"));
+        let note_path = vault_path.join("Daily").join("2024-01-15.md");
         assert!(note_path.exists());
 
         let content = fs::read_to_string(&note_path).unwrap();
         assert!(content.contains("フォルダ内メモ"));
     }
 
+    #[test]
+    fn test_append_memo_under_insert_after_heading() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+
+        // Obsidian設定とThino設定を作成
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        let daily_notes_json = r#"{"folder": "", "format": "YYYY-MM-DD"}"#;
+        fs::write(
+            vault_path.join(".obsidian").join("daily-notes.json"),
+            daily_notes_json,
+        )
+        .unwrap();
+        let plugin_dir = vault_path
+            .join(".obsidian")
+            .join("plugins")
+            .join("obsidian-memos");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        let thino_json = r###"{"InsertAfter": "## Journal", "DefaultPrefix": "Task"}"###;
+        fs::write(plugin_dir.join("data.json"), thino_json).unwrap();
+
+        // 既存のデイリーノートを作成
+        let note_path = vault_path.join("2024-01-15.md");
+        fs::write(&note_path, "## Journal\n\n## Notes\nnote\n").unwrap();
+
+        append_memo(&test_config(vault_path), "見出し配下", &test_clock()).unwrap();
+
+        let content = fs::read_to_string(&note_path).unwrap();
+        assert_eq!(
+            content,
+            "## Journal\n- [ ] 14:30 見出し配下\n\n## Notes\nnote\n"
+        );
+    }
+
+    #[test]
+    fn test_append_memo_config_insert_after_creates_heading() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        let daily_notes_json = r#"{"folder": "", "format": "YYYY-MM-DD"}"#;
+        fs::write(
+            vault_path.join(".obsidian").join("daily-notes.json"),
+            daily_notes_json,
+        )
+        .unwrap();
+
+        // 既存のデイリーノートを作成（挿入先ヘッダーなし）
+        let note_path = vault_path.join("2024-01-15.md");
+        fs::write(&note_path, "# Title\n").unwrap();
+
+        let config = Config {
+            insert_after: Some("## Journal".to_string()),
+            ..test_config(vault_path)
+        };
+        append_memo(&config, "設定ヘッダー", &test_clock()).unwrap();
+
+        let content = fs::read_to_string(&note_path).unwrap();
+        assert_eq!(content, "# Title\n\n## Journal\n- 14:30 設定ヘッダー\n");
+    }
+
+    #[test]
+    fn test_insert_target_prefers_config() {
+        let thino = ThinoSettings {
+            insert_after: "# Journal".to_string(),
+            ..ThinoSettings::default()
+        };
+        let mut config = test_config(Path::new("/vault"));
+        assert_eq!(insert_target(&config, &thino), "# Journal");
+
+        config.insert_after = Some("## Log".to_string());
+        assert_eq!(insert_target(&config, &thino), "## Log");
+    }
+
+    #[test]
+    fn test_append_memo_file_mode() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+
+        let config = Config {
+            save_mode: Some(SaveMode::File),
+            file_mode_folder: Some("Memos".to_string()),
+            ..test_config(vault_path)
+        };
+        append_memo(&config, "ファイルメモ", &test_clock()).unwrap();
+
+        // デイリーノートではなくメモファイルが作成される
+        assert!(!vault_path.join("2024-01-15.md").exists());
+
+        let entries: Vec<_> = fs::read_dir(vault_path.join("Memos"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(
+            entries,
+            [vault_path.join("Memos").join("20240115143000.md")]
+        );
+        let content = fs::read_to_string(&entries[0]).unwrap();
+        assert!(content.starts_with("---\ncreatedAt: "));
+        assert!(content.ends_with("---\nファイルメモ\n"));
+    }
+
+    #[test]
+    fn test_append_memo_multi_mode() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        let daily_notes_json = r#"{"folder": "Daily", "format": "YYYY-MM-DD"}"#;
+        fs::write(
+            vault_path.join(".obsidian").join("daily-notes.json"),
+            daily_notes_json,
+        )
+        .unwrap();
+
+        // 過去の日付ヘッダーを持つファイルを作成
+        let multi_path = vault_path.join("Memos.md");
+        fs::write(&multi_path, "## 2000-01-01\n- 09:00 old\n").unwrap();
+
+        let config = Config {
+            save_mode: Some(SaveMode::Multi),
+            multi_mode_file: Some("Memos".to_string()),
+            ..test_config(vault_path)
+        };
+        append_memo(&config, "マルチ1", &test_clock()).unwrap();
+        append_memo(&config, "マルチ2", &test_clock()).unwrap();
+
+        // デイリーノートは作成されない
+        assert!(!vault_path.join("Daily").exists());
+
+        let content = fs::read_to_string(&multi_path).unwrap();
+        assert_eq!(
+            content,
+            "## 2000-01-01\n- 09:00 old\n\n## 2024-01-15\n- 14:30 マルチ1\n- 14:30 マルチ2\n"
+        );
+    }
+
+    #[test]
+    fn test_append_memo_canvas_mode() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+
+        let config = Config {
+            save_mode: Some(SaveMode::Canvas),
+            canvas_mode_file: Some("Boards/memos".to_string()),
+            ..test_config(vault_path)
+        };
+        append_memo(&config, "キャンバス1", &test_clock()).unwrap();
+        append_memo(&config, "キャンバス2", &test_clock()).unwrap();
+
+        let canvas_path = vault_path.join("Boards").join("memos.canvas");
+        let canvas: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&canvas_path).unwrap()).unwrap();
+        let nodes = canvas["nodes"].as_array().unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0]["text"], "- 14:30 キャンバス1");
+        assert_eq!(nodes[1]["text"], "- 14:30 キャンバス2");
+        assert_ne!(nodes[0]["id"], nodes[1]["id"]);
+    }
+
+    #[test]
+    fn test_save_mode_prefers_config() {
+        let thino = ThinoSettings {
+            save_mode: SaveMode::File,
+            ..ThinoSettings::default()
+        };
+        let mut config = test_config(Path::new("/vault"));
+        assert_eq!(save_mode(&config, &thino), SaveMode::File);
+
+        config.save_mode = Some(SaveMode::Daily);
+        assert_eq!(save_mode(&config, &thino), SaveMode::Daily);
+    }
+
     #[test]
     fn test_append_memo_multiple() {
         let dir = tempdir().unwrap();
@@ -452,14 +1100,334 @@ mod tests {
         .unwrap();
 
         // 複数のメモを追記
-        append_memo(vault_path, "メモ1").unwrap();
-        append_memo(vault_path, "メモ2").unwrap();
+        append_memo(&test_config(vault_path), "メモ1", &test_clock()).unwrap();
+        append_memo(&test_config(vault_path), "メモ2", &test_clock()).unwrap();
+
+        let content = fs::read_to_string(vault_path.join("2024-01-15.md")).unwrap();
+        assert_eq!(content, "- 14:30 メモ1\n- 14:30 メモ2\n");
+    }
+
+    // ========================================
+    // append_memo_at テスト
+    // ========================================
+
+    fn test_timestamp(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_append_memo_at_inserts_in_time_order() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        let note_path = vault_path.join("2026-10-16.md");
+        fs::write(&note_path, "# 2026-10-16\n- 08:00 朝\n- 18:00 夜\n").unwrap();
+
+        let config = test_config(vault_path);
+        append_memo_at(&config, "昼", test_timestamp(16, 12, 30)).unwrap();
+        append_memo_at(&config, "深夜", test_timestamp(16, 23, 0)).unwrap();
+
+        let content = fs::read_to_string(&note_path).unwrap();
+        assert_eq!(
+            content,
+            "# 2026-10-16\n- 08:00 朝\n- 12:30 昼\n- 18:00 夜\n- 23:00 深夜\n"
+        );
+    }
+
+    #[test]
+    fn test_append_memo_at_creates_past_daily_note() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+
+        append_memo_at(
+            &test_config(vault_path),
+            "過去のメモ",
+            test_timestamp(1, 9, 15),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(vault_path.join("2026-10-01.md")).unwrap();
+        assert_eq!(content, "- 09:15 過去のメモ\n");
+    }
+
+    #[test]
+    fn test_append_memo_at_multi_mode_date_heading() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        fs::write(
+            vault_path.join("Thino.md"),
+            "## 2026-10-16\n- 10:00 a\n\n## 2026-10-17\n- 09:00 b\n",
+        )
+        .unwrap();
+        let config = Config {
+            save_mode: Some(SaveMode::Multi),
+            ..test_config(vault_path)
+        };
+
+        append_memo_at(&config, "c", test_timestamp(16, 8, 0)).unwrap();
+
+        let content = fs::read_to_string(vault_path.join("Thino.md")).unwrap();
+        assert_eq!(
+            content,
+            "## 2026-10-16\n- 08:00 c\n- 10:00 a\n\n## 2026-10-17\n- 09:00 b\n"
+        );
+    }
+
+    // ========================================
+    // day_start テスト
+    // ========================================
+
+    #[test]
+    fn test_note_date_day_start() {
+        let config = Config {
+            day_start: NaiveTime::from_hms_opt(4, 0, 0),
+            ..test_config(Path::new("/vault"))
+        };
+        let previous = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+
+        assert_eq!(note_date(&config, test_timestamp(15, 1, 30)), previous);
+        assert_eq!(note_date(&config, test_timestamp(15, 3, 59)), previous);
+        assert_eq!(note_date(&config, test_timestamp(15, 4, 0)), today);
+        assert_eq!(
+            note_date(&test_config(Path::new("/vault")), test_timestamp(15, 1, 30)),
+            today
+        );
+    }
+
+    #[test]
+    fn test_append_memo_after_midnight_goes_to_previous_day() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        fs::write(vault_path.join("2026-10-14.md"), "- 23:10 夜\n").unwrap();
+        let config = Config {
+            day_start: NaiveTime::from_hms_opt(4, 0, 0),
+            ..test_config(vault_path)
+        };
+
+        let clock = FixedClock(test_timestamp(15, 1, 30));
+        append_memo(&config, "深夜のメモ", &clock).unwrap();
+
+        // 時刻は実際の時刻のまま、前日のノートの末尾に追記される
+        let content = fs::read_to_string(vault_path.join("2026-10-14.md")).unwrap();
+        assert_eq!(content, "- 23:10 夜\n- 01:30 深夜のメモ\n");
+        assert!(!vault_path.join("2026-10-15.md").exists());
+    }
+
+    #[test]
+    fn test_append_memo_refuses_sync_conflict() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        fs::write(vault_path.join("2024-01-15.md"), "- 09:00 朝\n").unwrap();
+        fs::write(
+            vault_path.join("2024-01-15 (conflict).md"),
+            "- 09:05 別端末\n",
+        )
+        .unwrap();
+        let config = Config {
+            on_sync_conflict: Some(SyncConflictPolicy::Refuse),
+            ..test_config(vault_path)
+        };
+
+        let result = append_memo(&config, "競合中のメモ", &test_clock());
+
+        match result {
+            Err(MemoError::SyncConflict(path, copies)) => {
+                assert_eq!(path, vault_path.join("2024-01-15.md"));
+                assert_eq!(copies, vec![vault_path.join("2024-01-15 (conflict).md")]);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+        // ノートは変更されない
+        let content = fs::read_to_string(vault_path.join("2024-01-15.md")).unwrap();
+        assert_eq!(content, "- 09:00 朝\n");
+    }
+
+    #[test]
+    fn test_append_memo_warns_sync_conflict() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        fs::write(vault_path.join("2024-01-15.md"), "- 09:00 朝\n").unwrap();
+        fs::write(
+            vault_path.join("2024-01-15 (conflict).md"),
+            "- 09:05 別端末\n",
+        )
+        .unwrap();
+        fs::write(vault_path.join("2024-01-15 2.md"), "- 09:05 別のノート\n").unwrap();
+
+        // 既定では警告を返して書き込む（番号付きのファイルは対象外）
+        let warnings =
+            append_memo(&test_config(vault_path), "競合中のメモ", &test_clock()).unwrap();
+
+        match warnings.as_slice() {
+            [MemoWarning::SyncConflict(path, copies)] => {
+                assert_eq!(path, &vault_path.join("2024-01-15.md"));
+                assert_eq!(copies, &vec![vault_path.join("2024-01-15 (conflict).md")]);
+            }
+            other => panic!("unexpected warnings: {other:?}"),
+        }
+
+        let content = fs::read_to_string(vault_path.join("2024-01-15.md")).unwrap();
+        assert_eq!(content, "- 09:00 朝\n- 14:30 競合中のメモ\n");
+    }
+
+    #[test]
+    fn test_append_memo_preserves_crlf_and_bom() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        let note_path = vault_path.join("2024-01-15.md");
+        fs::write(&note_path, "\u{feff}# Title\r\n- 09:00 朝\r\n").unwrap();
+
+        append_memo(&test_config(vault_path), "1行目\n2行目", &test_clock()).unwrap();
 
-        let today = Local::now().format("%Y-%m-%d").to_string();
-        let note_path = vault_path.join(format!("{today}.md"));
         let content = fs::read_to_string(&note_path).unwrap();
+        assert_eq!(
+            content,
+            "\u{feff}# Title\r\n- 09:00 朝\r\n- 14:30 1行目\r\n2行目\r\n"
+        );
+    }
+
+    #[test]
+    fn test_append_memo_refuses_invalid_utf8() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        let note_path = vault_path.join("2024-01-15.md");
+        // Shift_JISで保存されたノート
+        let original = b"- 09:00 \x83\x81\x83\x82\n".to_vec();
+        fs::write(&note_path, &original).unwrap();
+
+        let result = append_memo(&test_config(vault_path), "メモ", &test_clock());
 
-        assert!(content.contains("メモ1"));
-        assert!(content.contains("メモ2"));
+        assert!(matches!(result, Err(MemoError::InvalidEncoding(path, _)) if path == note_path));
+        assert_eq!(fs::read(&note_path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_append_memo_applies_daily_note_template() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        fs::write(
+            vault_path.join(".obsidian").join("daily-notes.json"),
+            r#"{"folder": "Daily", "format": "YYYY-MM-DD", "template": "Templates/Daily"}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(vault_path.join("Templates")).unwrap();
+        fs::write(
+            vault_path.join("Templates").join("Daily.md"),
+            "# {{title}}\n{{date:dddd}} {{time}}\n\n## Journal\n",
+        )
+        .unwrap();
+        let config = Config {
+            insert_after: Some("## Journal".to_string()),
+            ..test_config(vault_path)
+        };
+
+        append_memo(&config, "最初のメモ", &test_clock()).unwrap();
+        append_memo(&config, "次のメモ", &test_clock()).unwrap();
+
+        // テンプレートは新しく作成したときだけ展開する
+        let content = fs::read_to_string(vault_path.join("Daily").join("2024-01-15.md")).unwrap();
+        assert_eq!(
+            content,
+            "# 2024-01-15\nMonday 14:30\n\n## Journal\n- 14:30 最初のメモ\n- 14:30 次のメモ\n"
+        );
+    }
+
+    #[test]
+    fn test_append_memo_invalid_template_creates_no_note() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        fs::write(
+            vault_path.join(".obsidian").join("daily-notes.json"),
+            r#"{"folder": "Daily", "format": "YYYY-MM-DD", "template": "Daily template"}"#,
+        )
+        .unwrap();
+        // Shift_JISで保存されたテンプレート
+        fs::write(
+            vault_path.join("Daily template.md"),
+            b"# \x83\x81\x83\x82\n",
+        )
+        .unwrap();
+
+        let result = append_memo(&test_config(vault_path), "メモ", &test_clock());
+
+        assert!(matches!(result, Err(MemoError::InvalidTemplate(..))));
+        assert!(!vault_path.join("Daily").join("2024-01-15.md").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_append_memo_through_symlinked_note() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path().join("vault");
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        let shared = dir.path().join("shared.md");
+        fs::write(&shared, "- 09:00 朝\n").unwrap();
+        let note_path = vault_path.join("2024-01-15.md");
+        std::os::unix::fs::symlink(&shared, &note_path).unwrap();
+
+        append_memo(&test_config(&vault_path), "リンク経由", &test_clock()).unwrap();
+
+        assert!(
+            fs::symlink_metadata(&note_path)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(
+            fs::read_to_string(&shared).unwrap(),
+            "- 09:00 朝\n- 14:30 リンク経由\n"
+        );
+    }
+
+    #[test]
+    fn test_append_memo_icloud_conflicts() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        fs::write(vault_path.join("2024-01-15.md"), "- 09:00 朝\n").unwrap();
+        fs::write(vault_path.join("2024-01-15 2.md"), "- 09:05 別端末\n").unwrap();
+        let config = Config {
+            on_sync_conflict: Some(SyncConflictPolicy::Refuse),
+            icloud_conflicts: Some(true),
+            ..test_config(vault_path)
+        };
+
+        let result = append_memo(&config, "競合中のメモ", &test_clock());
+
+        assert!(
+            matches!(result, Err(MemoError::SyncConflict(_, copies)) if copies == vec![vault_path.join("2024-01-15 2.md")])
+        );
+    }
+
+    #[test]
+    fn test_append_memo_multi_mode_ignores_numbered_files() {
+        let dir = tempdir().unwrap();
+        let vault_path = dir.path();
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        fs::write(vault_path.join("Thino 2.md"), "別のファイル\n").unwrap();
+        let config = Config {
+            save_mode: Some(SaveMode::Multi),
+            on_sync_conflict: Some(SyncConflictPolicy::Refuse),
+            icloud_conflicts: Some(true),
+            ..test_config(vault_path)
+        };
+
+        let warnings = append_memo(&config, "メモ", &test_clock()).unwrap();
+
+        assert!(warnings.is_empty());
+        assert!(vault_path.join("Thino.md").exists());
     }
 }
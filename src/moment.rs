@@ -0,0 +1,875 @@
+//! moment.js形式の日付フォーマット
+//!
+//! Obsidianが使用するmoment.jsのフォーマットトークンを解釈し、日付・時刻の文字列を生成する。
+//! ロケールは英語（moment.jsのデフォルト）として扱う。
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+/// 解釈するフォーマットトークン
+///
+/// 同じ文字で始まるトークンは長いものから順に並べ、最長一致で切り出す。
+const PATTERNS: &[&str] = &[
+    "YYYYYY", "YYYYY", "YYYY", "YY", "Y", "ggggg", "gggg", "gg", "GGGGG", "GGGG", "GG", "MMMM",
+    "MMM", "MM", "Mo", "M", "Qo", "Q", "DDDD", "DDDo", "DDD", "DD", "Do", "D", "dddd", "ddd", "dd",
+    "do", "d", "e", "E", "wo", "ww", "w", "Wo", "WW", "W", "HH", "H", "hh", "h", "kk", "k", "mm",
+    "m", "ss", "s", "SSS", "SS", "S", "A", "a",
+];
+
+/// 月名
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// 月名（短縮形）
+const MONTHS_SHORT: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// 曜日名（日曜始まり）
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// 曜日名（短縮形）
+const WEEKDAYS_SHORT: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// 曜日名（最短形）
+const WEEKDAYS_MIN: [&str; 7] = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
+
+/// 英語ロケールの週の開始曜日（日曜日）
+const LOCALE_DOW: u32 = 0;
+
+/// 英語ロケールの第1週の基準（1月1日を含む週が第1週）
+const LOCALE_DOY: u32 = 6;
+
+/// ISO週の開始曜日（月曜日）
+const ISO_DOW: u32 = 1;
+
+/// ISO週の第1週の基準（1月4日を含む週が第1週）
+const ISO_DOY: u32 = 4;
+
+/// フォーマット文字列の構成要素
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    /// そのまま出力する文字列
+    Literal(String),
+    /// フォーマットトークン
+    Pattern(&'static str),
+}
+
+/// moment.js形式のフォーマットで日付を文字列に変換する
+///
+/// # 引数
+///
+/// * `format` - moment.js形式のフォーマット文字列（例: "YYYY-MM-DD"）
+/// * `date` - 変換する日付
+///
+/// # 戻り値
+///
+/// フォーマットされた日付文字列。トークンでない文字はそのまま出力する。
+/// 時刻のトークンは0時0分0秒として変換する。
+pub fn format(format: &str, date: NaiveDate) -> String {
+    format_datetime(format, date.and_time(NaiveTime::MIN))
+}
+
+/// moment.js形式のフォーマットで日時を文字列に変換する
+///
+/// # 引数
+///
+/// * `format` - moment.js形式のフォーマット文字列（例: "HH:mm"、"h:mm A"）
+/// * `datetime` - 変換する日時
+///
+/// # 戻り値
+///
+/// フォーマットされた文字列。トークンでない文字はそのまま出力する。
+pub fn format_datetime(format: &str, datetime: NaiveDateTime) -> String {
+    tokenize(format)
+        .into_iter()
+        .map(|token| match token {
+            Token::Literal(text) => text,
+            Token::Pattern(pattern) => format_pattern(pattern, datetime),
+        })
+        .collect()
+}
+
+/// moment.js形式のフォーマットで書かれた日付文字列を読み取る
+///
+/// 年・月・日、または年と年内通算日を含むフォーマットに対応する。読み取った日付を
+/// 同じフォーマットで変換し直し、元の文字列と一致した場合のみ日付を返す。
+///
+/// # 引数
+///
+/// * `format` - moment.js形式のフォーマット文字列（例: "YYYY-MM-DD"）
+/// * `text` - 読み取る文字列
+///
+/// # 戻り値
+///
+/// 読み取った日付。フォーマットに一致しない場合や日付を特定できない場合は `None`。
+pub fn parse(format: &str, text: &str) -> Option<NaiveDate> {
+    let (fields, rest) = parse_fields(format, text)?;
+    if !rest.is_empty() {
+        return None;
+    }
+
+    let date = fields.to_date()?;
+    (self::format(format, date) == text).then_some(date)
+}
+
+/// 文字列の先頭からmoment.js形式のフォーマットで書かれた時刻を読み取る
+///
+/// 時（`H` / `h` / `k` 系）を含むフォーマットに対応する。読み取った時刻を同じフォーマットで
+/// 変換し直し、読み取った部分と一致した場合のみ時刻を返す。
+///
+/// # 引数
+///
+/// * `format` - moment.js形式のフォーマット文字列（例: "HH:mm"、"h:mm A"）
+/// * `text` - 読み取る文字列
+///
+/// # 戻り値
+///
+/// 読み取った時刻と残りの文字列。フォーマットに一致しない場合は `None`。
+pub fn parse_time_prefix<'a>(format: &str, text: &'a str) -> Option<(NaiveTime, &'a str)> {
+    let (fields, rest) = parse_fields(format, text)?;
+    let time = fields.to_time()?;
+
+    let consumed = &text[..text.len() - rest.len()];
+    let date = fields.to_date().unwrap_or_default();
+    (format_datetime(format, date.and_time(time)) == consumed).then_some((time, rest))
+}
+
+/// フォーマットに従って文字列の先頭から日時の構成要素を読み取り、残りの文字列を返す
+fn parse_fields<'a>(format: &str, text: &'a str) -> Option<(DateFields, &'a str)> {
+    let mut fields = DateFields::default();
+    let mut rest = text;
+
+    for token in tokenize(format) {
+        rest = match token {
+            Token::Literal(literal) => rest.strip_prefix(literal.as_str())?,
+            Token::Pattern(pattern) => parse_pattern(pattern, rest, &mut fields)?,
+        };
+    }
+
+    Some((fields, rest))
+}
+
+/// 読み取った日時の構成要素
+#[derive(Default)]
+struct DateFields {
+    year: Option<i32>,
+    month: Option<u32>,
+    day: Option<u32>,
+    day_of_year: Option<u32>,
+    /// 24時間制の時（`k` 系の24は0時）
+    hour: Option<u32>,
+    /// 12時間制の時
+    hour12: Option<u32>,
+    /// 午後かどうか
+    pm: Option<bool>,
+    minute: Option<u32>,
+    second: Option<u32>,
+    nanosecond: Option<u32>,
+}
+
+impl DateFields {
+    /// 構成要素から日付を組み立てる
+    fn to_date(&self) -> Option<NaiveDate> {
+        let year = self.year?;
+        match (self.month, self.day, self.day_of_year) {
+            (Some(month), Some(day), _) => NaiveDate::from_ymd_opt(year, month, day),
+            (_, _, Some(day_of_year)) => NaiveDate::from_yo_opt(year, day_of_year),
+            _ => None,
+        }
+    }
+
+    /// 構成要素から時刻を組み立てる
+    ///
+    /// 12時間制の時は午前・午後の指定がなければ午前とみなす。
+    fn to_time(&self) -> Option<NaiveTime> {
+        let hour = match (self.hour, self.hour12) {
+            (Some(hour), _) => hour % 24,
+            (None, Some(hour)) => hour % 12 + if self.pm == Some(true) { 12 } else { 0 },
+            (None, None) => return None,
+        };
+        NaiveTime::from_hms_nano_opt(
+            hour,
+            self.minute.unwrap_or(0),
+            self.second.unwrap_or(0),
+            self.nanosecond.unwrap_or(0),
+        )
+    }
+}
+
+/// トークンに対応する部分を読み取り、残りの文字列を返す
+///
+/// 日付の特定に使わないトークン（曜日・週・四半期）は読み飛ばすのみ。
+fn parse_pattern<'a>(pattern: &str, text: &'a str, fields: &mut DateFields) -> Option<&'a str> {
+    let skip_number = |min, max| take_number(text, min, max).map(|(_, rest)| rest);
+    // 1文字のトークンは1〜2桁、2文字のトークンは2桁
+    let time_number = |field: &mut Option<u32>| {
+        let min = pattern.len().min(2);
+        let (number, rest) = take_number(text, min, 2)?;
+        *field = Some(number);
+        Some(rest)
+    };
+    let skip_ordinal = |max| skip_ordinal_suffix(take_number(text, 1, max)?.1);
+    let skip_name = |names: &[&str]| take_name(text, names).map(|(_, rest)| rest);
+
+    match pattern {
+        "YYYYYY" => {
+            let (sign, digits) = text.split_at_checked(1)?;
+            let (year, rest) = take_number(digits, 6, 6)?;
+            let year = i32::try_from(year).ok()?;
+            fields.year = Some(match sign {
+                "+" => year,
+                "-" => -year,
+                _ => return None,
+            });
+            Some(rest)
+        }
+        "YYYYY" | "YYYY" | "Y" => {
+            let width = pattern.len().max(4);
+            let (year, rest) = take_number(text, width, width)?;
+            fields.year = Some(i32::try_from(year).ok()?);
+            Some(rest)
+        }
+        "YY" => {
+            // moment.jsと同じく68以下は2000年代、69以上は1900年代とみなす
+            let (year, rest) = take_number(text, 2, 2)?;
+            let century = if year > 68 { 1900 } else { 2000 };
+            fields.year = Some(i32::try_from(century + year).ok()?);
+            Some(rest)
+        }
+        "M" | "MM" | "Mo" => {
+            let (month, rest) = take_day_number(pattern, text)?;
+            fields.month = Some(month);
+            Some(rest)
+        }
+        "D" | "DD" | "Do" => {
+            let (day, rest) = take_day_number(pattern, text)?;
+            fields.day = Some(day);
+            Some(rest)
+        }
+        "DDD" | "DDDD" | "DDDo" => {
+            let (day_of_year, rest) = take_day_number(pattern, text)?;
+            fields.day_of_year = Some(day_of_year);
+            Some(rest)
+        }
+        "MMM" | "MMMM" => {
+            let names = if pattern == "MMM" {
+                &MONTHS_SHORT
+            } else {
+                &MONTHS
+            };
+            let (index, rest) = take_name(text, names)?;
+            fields.month = Some(u32::try_from(index).ok()? + 1);
+            Some(rest)
+        }
+        "dd" => skip_name(&WEEKDAYS_MIN),
+        "ddd" => skip_name(&WEEKDAYS_SHORT),
+        "dddd" => skip_name(&WEEKDAYS),
+        "Q" | "d" | "e" | "E" => skip_number(1, 1),
+        "Qo" | "do" => skip_ordinal(1),
+        "w" | "W" => skip_number(1, 2),
+        "wo" | "Wo" => skip_ordinal(2),
+        "ww" | "WW" | "gg" | "GG" => skip_number(2, 2),
+        "gggg" | "GGGG" => skip_number(4, 4),
+        "ggggg" | "GGGGG" => skip_number(5, 5),
+        "H" | "HH" | "k" | "kk" => time_number(&mut fields.hour),
+        "h" | "hh" => time_number(&mut fields.hour12),
+        "m" | "mm" => time_number(&mut fields.minute),
+        "s" | "ss" => time_number(&mut fields.second),
+        "S" | "SS" | "SSS" => {
+            let (fraction, rest) = take_number(text, pattern.len(), pattern.len())?;
+            fields.nanosecond = Some(fraction * 10u32.pow(9 - pattern.len() as u32));
+            Some(rest)
+        }
+        "a" | "A" => {
+            let names = if pattern == "a" {
+                ["am", "pm"]
+            } else {
+                ["AM", "PM"]
+            };
+            let (index, rest) = take_name(text, &names)?;
+            fields.pm = Some(index == 1);
+            Some(rest)
+        }
+        _ => text.strip_prefix(pattern),
+    }
+}
+
+/// 月・日・通算日のトークンに対応する数値を読み取る
+///
+/// 2文字のトークン（`MM` / `DD`）は2桁、`DDDD` は3桁固定、序数（`o`）は接尾辞付き。
+fn take_day_number<'a>(pattern: &str, text: &'a str) -> Option<(u32, &'a str)> {
+    let max = if pattern.starts_with("DDD") { 3 } else { 2 };
+    if pattern.ends_with('o') {
+        let (number, rest) = take_number(text, 1, max)?;
+        return Some((number, skip_ordinal_suffix(rest)?));
+    }
+
+    match pattern {
+        "MM" | "DD" => take_number(text, 2, 2),
+        "DDDD" => take_number(text, 3, 3),
+        _ => take_number(text, 1, max),
+    }
+}
+
+/// 先頭の数字（`min`〜`max` 桁）を読み取る
+fn take_number(text: &str, min: usize, max: usize) -> Option<(u32, &str)> {
+    let len = text
+        .bytes()
+        .take(max)
+        .take_while(u8::is_ascii_digit)
+        .count();
+    if len < min {
+        return None;
+    }
+    let (digits, rest) = text.split_at(len);
+    Some((digits.parse().ok()?, rest))
+}
+
+/// 先頭の名前（月名・曜日名）を読み取り、一覧内の位置を返す
+fn take_name<'a>(text: &'a str, names: &[&str]) -> Option<(usize, &'a str)> {
+    names
+        .iter()
+        .enumerate()
+        .find_map(|(index, name)| Some((index, text.strip_prefix(name)?)))
+}
+
+/// 序数の接尾辞（st / nd / rd / th）を読み飛ばす
+fn skip_ordinal_suffix(text: &str) -> Option<&str> {
+    ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| text.strip_prefix(suffix))
+}
+
+/// フォーマット文字列をトークンに分割する
+///
+/// `[...]` で囲まれた範囲と `\` の直後のトークン（トークンでなければ1文字）は
+/// リテラルとして扱う。
+fn tokenize(format: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = format;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '['
+            && let Some(end) = rest[1..].find(['[', ']'])
+            && rest.as_bytes()[end + 1] == b']'
+        {
+            tokens.push(Token::Literal(rest[1..=end].to_string()));
+            rest = &rest[end + 2..];
+            continue;
+        }
+
+        if c == '\\' {
+            let len = next_token_len(&rest[1..]);
+            tokens.push(Token::Literal(rest[1..=len].to_string()));
+            rest = &rest[1 + len..];
+            continue;
+        }
+
+        let (text, remaining) = rest.split_at(next_token_len(rest));
+        match PATTERNS.iter().find(|pattern| **pattern == text) {
+            Some(pattern) => tokens.push(Token::Pattern(pattern)),
+            None => tokens.push(Token::Literal(text.to_string())),
+        }
+        rest = remaining;
+    }
+
+    tokens
+}
+
+/// 先頭のトークンのバイト長を返す
+///
+/// トークンに一致しない場合は先頭1文字の長さ。
+fn next_token_len(text: &str) -> usize {
+    PATTERNS
+        .iter()
+        .find(|pattern| text.starts_with(**pattern))
+        .map_or_else(
+            || text.chars().next().map_or(0, char::len_utf8),
+            |pattern| pattern.len(),
+        )
+}
+
+/// トークンを日時の値に変換する
+fn format_pattern(pattern: &str, datetime: NaiveDateTime) -> String {
+    let date = datetime.date();
+    let hour = datetime.hour();
+    let hour12 = match hour % 12 {
+        0 => 12,
+        hour => hour,
+    };
+    let year = i64::from(date.year());
+    let month = date.month0() as usize;
+    let weekday = date.weekday().num_days_from_sunday();
+
+    match pattern {
+        "YYYYYY" => zero_fill(year, 6, true),
+        "YYYYY" => zero_fill(year, 5, false),
+        "YYYY" => zero_fill(year, 4, false),
+        "Y" if year > 9999 => format!("+{year}"),
+        "Y" => zero_fill(year, 4, false),
+        "YY" => zero_fill(year % 100, 2, false),
+        "gg" | "gggg" | "ggggg" => format_week_year(pattern, date, LOCALE_DOW, LOCALE_DOY),
+        "GG" | "GGGG" | "GGGGG" => format_week_year(pattern, date, ISO_DOW, ISO_DOY),
+        "M" => (month + 1).to_string(),
+        "Mo" => ordinal(month as i64 + 1),
+        "MM" => zero_fill(month as i64 + 1, 2, false),
+        "MMM" => MONTHS_SHORT[month].to_string(),
+        "MMMM" => MONTHS[month].to_string(),
+        "Q" => (month / 3 + 1).to_string(),
+        "Qo" => ordinal(month as i64 / 3 + 1),
+        "D" => date.day().to_string(),
+        "Do" => ordinal(i64::from(date.day())),
+        "DD" => zero_fill(i64::from(date.day()), 2, false),
+        "DDD" => date.ordinal().to_string(),
+        "DDDo" => ordinal(i64::from(date.ordinal())),
+        "DDDD" => zero_fill(i64::from(date.ordinal()), 3, false),
+        "d" => weekday.to_string(),
+        "do" => ordinal(i64::from(weekday)),
+        "dd" => WEEKDAYS_MIN[weekday as usize].to_string(),
+        "ddd" => WEEKDAYS_SHORT[weekday as usize].to_string(),
+        "dddd" => WEEKDAYS[weekday as usize].to_string(),
+        "e" => ((weekday + 7 - LOCALE_DOW) % 7).to_string(),
+        "E" => date.weekday().number_from_monday().to_string(),
+        "w" | "wo" | "ww" => format_week(pattern, date, LOCALE_DOW, LOCALE_DOY),
+        "W" | "Wo" | "WW" => format_week(pattern, date, ISO_DOW, ISO_DOY),
+        "H" => hour.to_string(),
+        "HH" => zero_fill(i64::from(hour), 2, false),
+        "h" => hour12.to_string(),
+        "hh" => zero_fill(i64::from(hour12), 2, false),
+        "k" => (if hour == 0 { 24 } else { hour }).to_string(),
+        "kk" => zero_fill(i64::from(if hour == 0 { 24 } else { hour }), 2, false),
+        "m" => datetime.minute().to_string(),
+        "mm" => zero_fill(i64::from(datetime.minute()), 2, false),
+        "s" => datetime.second().to_string(),
+        "ss" => zero_fill(i64::from(datetime.second()), 2, false),
+        "S" | "SS" | "SSS" => {
+            let digits = pattern.len();
+            let fraction = datetime.nanosecond() % 1_000_000_000 / 10u32.pow(9 - digits as u32);
+            zero_fill(i64::from(fraction), digits, false)
+        }
+        "a" => (if hour < 12 { "am" } else { "pm" }).to_string(),
+        "A" => (if hour < 12 { "AM" } else { "PM" }).to_string(),
+        _ => pattern.to_string(),
+    }
+}
+
+/// 週番号トークン（w / wo / ww 系）を変換する
+fn format_week(pattern: &str, date: NaiveDate, dow: u32, doy: u32) -> String {
+    let (_, week) = week_of_year(date, dow, doy);
+    match pattern.len() {
+        1 => week.to_string(),
+        _ if pattern.ends_with('o') => ordinal(week),
+        _ => zero_fill(week, 2, false),
+    }
+}
+
+/// 週の年トークン（gg / gggg / ggggg 系）を変換する
+fn format_week_year(pattern: &str, date: NaiveDate, dow: u32, doy: u32) -> String {
+    let (year, _) = week_of_year(date, dow, doy);
+    match pattern.len() {
+        2 => zero_fill(year % 100, 2, false),
+        len => zero_fill(year, len, false),
+    }
+}
+
+/// 週の年と週番号を返す
+///
+/// moment.jsの `weekOfYear` と同じ計算。`dow` は週の開始曜日（0 = 日曜日）、
+/// `doy` は第1週を決める基準（`7 + dow - doy` 日目の1月の日付を含む週が第1週）。
+fn week_of_year(date: NaiveDate, dow: u32, doy: u32) -> (i64, i64) {
+    let year = date.year();
+    let week =
+        (i64::from(date.ordinal()) - first_week_offset(year, dow, doy) - 1).div_euclid(7) + 1;
+
+    if week < 1 {
+        let prev = year - 1;
+        (i64::from(prev), week + weeks_in_year(prev, dow, doy))
+    } else if week > weeks_in_year(year, dow, doy) {
+        (i64::from(year) + 1, week - weeks_in_year(year, dow, doy))
+    } else {
+        (i64::from(year), week)
+    }
+}
+
+/// 第1週の開始日の、1月1日からのオフセット（日数）を返す
+fn first_week_offset(year: i32, dow: u32, doy: u32) -> i64 {
+    let fwd = 7 + dow - doy;
+    let fwd_weekday = NaiveDate::from_ymd_opt(year, 1, fwd)
+        .expect("first week day is always a valid January date")
+        .weekday()
+        .num_days_from_sunday();
+    let fwdlw = (7 + fwd_weekday - dow) % 7;
+    i64::from(fwd) - i64::from(fwdlw) - 1
+}
+
+/// 年に含まれる週の数を返す
+fn weeks_in_year(year: i32, dow: u32, doy: u32) -> i64 {
+    let days = if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
+        366
+    } else {
+        365
+    };
+    (days - first_week_offset(year, dow, doy) + first_week_offset(year + 1, dow, doy)) / 7
+}
+
+/// 英語の序数表記を返す（例: 1st, 2nd, 3rd, 11th）
+fn ordinal(number: i64) -> String {
+    let suffix = if (number % 100) / 10 == 1 {
+        "th"
+    } else {
+        match number % 10 {
+            1 => "st",
+            2 => "nd",
+            3 => "rd",
+            _ => "th",
+        }
+    };
+    format!("{number}{suffix}")
+}
+
+/// 指定桁数になるようゼロ埋めする
+///
+/// 負の数は `-` を、`force_sign` が真の正の数は `+` を先頭に付ける。
+fn zero_fill(number: i64, width: usize, force_sign: bool) -> String {
+    let sign = if number < 0 {
+        "-"
+    } else if force_sign {
+        "+"
+    } else {
+        ""
+    };
+    format!("{sign}{:0width$}", number.unsigned_abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    // ========================================
+    // tokenize テスト
+    // ========================================
+
+    #[test]
+    fn test_tokenize_longest_match() {
+        assert_eq!(
+            tokenize("MMMMDo"),
+            vec![Token::Pattern("MMMM"), Token::Pattern("Do")]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_escaped_literal() {
+        assert_eq!(
+            tokenize("[Week] W"),
+            vec![
+                Token::Literal("Week".to_string()),
+                Token::Literal(" ".to_string()),
+                Token::Pattern("W"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_backslash_escape() {
+        // moment.jsと同じく、バックスラッシュは直後のトークン全体をエスケープする
+        assert_eq!(
+            tokenize("\\YYYY-\\x"),
+            vec![
+                Token::Literal("YYYY".to_string()),
+                Token::Literal("-".to_string()),
+                Token::Literal("x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_nested_bracket() {
+        // 閉じ括弧の前に開き括弧がある場合、最初の `[` はリテラル
+        assert_eq!(
+            tokenize("[x[b]"),
+            vec![
+                Token::Literal("[".to_string()),
+                Token::Literal("x".to_string()),
+                Token::Literal("b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_unclosed_bracket_is_literal() {
+        assert_eq!(
+            tokenize("[YY"),
+            vec![Token::Literal("[".to_string()), Token::Pattern("YY")]
+        );
+    }
+
+    // ========================================
+    // format テスト（moment.jsの出力と比較）
+    // ========================================
+
+    #[test]
+    fn test_format_matrix() {
+        // (フォーマット, 日付, moment.jsの出力)
+        let cases = [
+            ("YYYY-MM-DD", date(2026, 1, 3), "2026-01-03"),
+            ("YY-M-D", date(2026, 1, 3), "26-1-3"),
+            ("YYYY-MM-DD ddd", date(2026, 1, 3), "2026-01-03 Sat"),
+            ("dddd, YYYY-MM-DD", date(2026, 1, 3), "Saturday, 2026-01-03"),
+            ("dd", date(2026, 1, 3), "Sa"),
+            ("d do e E", date(2026, 1, 3), "6 6th 6 6"),
+            ("d do e E", date(2026, 10, 18), "0 0th 0 7"),
+            ("DD MMM YYYY", date(2026, 1, 3), "03 Jan 2026"),
+            ("MMMM DD, YYYY", date(2026, 1, 3), "January 03, 2026"),
+            ("MMMM Do, YYYY", date(2026, 1, 3), "January 3rd, 2026"),
+            ("Mo", date(2026, 2, 1), "2nd"),
+            ("Do", date(2026, 1, 11), "11th"),
+            ("Do", date(2026, 1, 12), "12th"),
+            ("Do", date(2026, 1, 13), "13th"),
+            ("Do", date(2026, 1, 21), "21st"),
+            ("Do", date(2026, 1, 22), "22nd"),
+            ("Do", date(2026, 1, 23), "23rd"),
+            ("DDD DDDD DDDo", date(2026, 1, 3), "3 003 3rd"),
+            ("DDD DDDD", date(2024, 12, 31), "366 366"),
+            ("Q Qo", date(2026, 1, 3), "1 1st"),
+            ("Q Qo", date(2026, 10, 18), "4 4th"),
+            ("YYYY-[W]WW", date(2026, 1, 3), "2026-W01"),
+            ("YYYY-wo", date(2026, 1, 3), "2026-1st"),
+            ("gggg-[W]ww", date(2026, 10, 18), "2026-W43"),
+            ("GGGG-[W]WW-E", date(2026, 10, 18), "2026-W42-7"),
+            ("GGGG-[W]WW", date(2024, 12, 30), "2025-W01"),
+            ("gggg-[W]ww", date(2024, 12, 30), "2025-W01"),
+            ("GGGG-[W]WW", date(2021, 1, 1), "2020-W53"),
+            ("gggg-[W]ww", date(2021, 1, 1), "2021-W01"),
+            ("GG gg", date(2021, 1, 1), "20 21"),
+            ("gggg-[W]ww", date(2022, 12, 31), "2022-W53"),
+            ("gggg-[W]ww", date(2023, 1, 1), "2023-W01"),
+            ("[Daily] YYYY/MM/DD", date(2026, 1, 3), "Daily 2026/01/03"),
+            ("[YYYY] YYYY", date(2026, 1, 3), "YYYY 2026"),
+            ("YYYY/[Q]Q/MM-DD", date(2026, 10, 18), "2026/Q4/10-18"),
+            ("YYYYY YYYYYY", date(2026, 1, 3), "02026 +002026"),
+            ("YY", date(2005, 6, 1), "05"),
+            ("YYYY", date(999, 6, 1), "0999"),
+            ("Y", date(999, 6, 1), "0999"),
+            ("YYYY Y", date(10000, 1, 1), "10000 +10000"),
+            ("[note]_YYYY-MM-DD", date(2026, 1, 3), "note_2026-01-03"),
+            // エスケープしない英字もトークンとして解釈される（"e" はロケールの曜日）
+            ("note_YYYY-MM-DD", date(2026, 1, 3), "not6_2026-01-03"),
+            ("日記 YYYY年M月D日", date(2026, 1, 3), "日記 2026年1月3日"),
+        ];
+
+        for (fmt, date, expected) in cases {
+            assert_eq!(format(fmt, date), expected, "format: {fmt}, date: {date}");
+        }
+    }
+
+    #[test]
+    fn test_iso_week_matches_chrono() {
+        // ISO週の計算はchronoの実装と一致する
+        let mut date = date(2019, 12, 1);
+        while date < NaiveDate::from_ymd_opt(2027, 2, 1).unwrap() {
+            let iso = date.iso_week();
+            assert_eq!(
+                week_of_year(date, ISO_DOW, ISO_DOY),
+                (i64::from(iso.year()), i64::from(iso.week())),
+                "date: {date}"
+            );
+            date = date.succ_opt().unwrap();
+        }
+    }
+
+    // ========================================
+    // parse テスト
+    // ========================================
+
+    #[test]
+    fn test_parse_round_trip() {
+        let formats = [
+            "YYYY-MM-DD",
+            "YYYY/MM/DD",
+            "YYYYMMDD",
+            "DD.MM.YYYY",
+            "YY-M-D",
+            "YYYY-MM-DD ddd",
+            "dddd, MMMM Do YYYY",
+            "DD MMM YYYY",
+            "YYYY/MM-MMMM/YYYY-MM-DD-dddd",
+            "[Daily] YYYY-[Q]Q-MM-DD",
+            "YYYY-[W]WW-DD.MM",
+            "YYYY-DDDD",
+            "YYYY-DDDo",
+            "gggg-[W]ww-YYYY.MM.DD",
+        ];
+        let mut date = date(2024, 12, 20);
+        while date < NaiveDate::from_ymd_opt(2025, 3, 10).unwrap() {
+            for fmt in formats {
+                assert_eq!(parse(fmt, &format(fmt, date)), Some(date), "format: {fmt}");
+            }
+            date = date.succ_opt().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_mismatch() {
+        assert_eq!(parse("YYYY-MM-DD", "2026-1-03"), None);
+        assert_eq!(parse("YYYY-MM-DD", "2026-01-03 copy"), None);
+        assert_eq!(parse("YYYY-MM-DD", "2026-02-30"), None);
+        assert_eq!(parse("YYYY-MM-DD", "Meeting notes"), None);
+        // 曜日が日付と一致しない
+        assert_eq!(parse("YYYY-MM-DD ddd", "2026-01-03 Mon"), None);
+    }
+
+    #[test]
+    fn test_parse_requires_full_date() {
+        // 週のみのフォーマットでは日付を特定できない
+        assert_eq!(parse("gggg-[W]ww", "2026-W01"), None);
+        assert_eq!(parse("YYYY-MM", "2026-01"), None);
+    }
+
+    #[test]
+    fn test_parse_two_digit_year() {
+        assert_eq!(parse("YY-MM-DD", "26-01-03"), Some(date(2026, 1, 3)));
+        assert_eq!(parse("YY-MM-DD", "99-01-03"), Some(date(1999, 1, 3)));
+    }
+
+    // ========================================
+    // 時刻 テスト
+    // ========================================
+
+    fn datetime(hour: u32, minute: u32, second: u32, milli: u32) -> NaiveDateTime {
+        date(2026, 1, 3)
+            .and_hms_milli_opt(hour, minute, second, milli)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_format_datetime_matrix() {
+        // (フォーマット, 日時, moment.jsの出力)
+        let cases = [
+            ("HH:mm", datetime(9, 5, 7, 0), "09:05"),
+            ("H:m:s", datetime(9, 5, 7, 0), "9:5:7"),
+            ("HH:mm:ss", datetime(21, 30, 0, 0), "21:30:00"),
+            ("h:mm A", datetime(0, 15, 0, 0), "12:15 AM"),
+            ("h:mm A", datetime(12, 15, 0, 0), "12:15 PM"),
+            ("hh:mm a", datetime(21, 30, 0, 0), "09:30 pm"),
+            ("k:mm kk", datetime(0, 30, 0, 0), "24:30 24"),
+            ("k:mm", datetime(13, 30, 0, 0), "13:30"),
+            ("HH:mm:ss.SSS", datetime(9, 5, 7, 42), "09:05:07.042"),
+            ("ss.S SS", datetime(9, 5, 7, 987), "07.9 98"),
+            ("YYYY-MM-DD HH:mm", datetime(9, 5, 0, 0), "2026-01-03 09:05"),
+        ];
+
+        for (fmt, datetime, expected) in cases {
+            assert_eq!(
+                format_datetime(fmt, datetime),
+                expected,
+                "format: {fmt}, datetime: {datetime}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_time_prefix_round_trip() {
+        let formats = [
+            "HH:mm",
+            "H:mm",
+            "HH:mm:ss",
+            "h:mm A",
+            "hh:mm a",
+            "h:mm:ss A",
+            "kk:mm",
+            "k:mm",
+            "HH:mm:ss.SSS",
+        ];
+        let mut time = NaiveTime::MIN;
+        loop {
+            for fmt in formats {
+                let text = format_datetime(fmt, date(2026, 1, 3).and_time(time));
+                let expected = if fmt.ends_with("SSS") || fmt.contains("ss") {
+                    time
+                } else {
+                    time.with_second(0).unwrap()
+                };
+                assert_eq!(
+                    parse_time_prefix(fmt, &format!("{text} memo")),
+                    Some((expected, " memo")),
+                    "format: {fmt}, text: {text}"
+                );
+            }
+            let (next, wrapped) =
+                time.overflowing_add_signed(chrono::Duration::seconds(7 * 60 + 13));
+            if wrapped != 0 {
+                break;
+            }
+            time = next;
+        }
+    }
+
+    #[test]
+    fn test_parse_time_prefix_rejects_mismatch() {
+        assert_eq!(parse_time_prefix("HH:mm", "9:05 memo"), None);
+        assert_eq!(parse_time_prefix("HH:mm", "25:00 memo"), None);
+        assert_eq!(parse_time_prefix("h:mm A", "13:00 PM memo"), None);
+        assert_eq!(parse_time_prefix("h:mm A", "0:30 AM memo"), None);
+        assert_eq!(parse_time_prefix("h:mm A", "9:30 memo"), None);
+        assert_eq!(parse_time_prefix("HH:mm", "memo"), None);
+        // 時を含まないフォーマットでは時刻を特定できない
+        assert_eq!(parse_time_prefix("mm:ss", "05:07 memo"), None);
+    }
+
+    #[test]
+    fn test_parse_time_prefix_twelve_hour() {
+        let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        assert_eq!(
+            parse_time_prefix("h:mm A", "12:15 AM x"),
+            Some((time(0, 15), " x"))
+        );
+        assert_eq!(
+            parse_time_prefix("h:mm a", "9:30 pm x"),
+            Some((time(21, 30), " x"))
+        );
+        assert_eq!(parse_time_prefix("k:mm", "24:30"), Some((time(0, 30), "")));
+    }
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!(ordinal(0), "0th");
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(2), "2nd");
+        assert_eq!(ordinal(3), "3rd");
+        assert_eq!(ordinal(4), "4th");
+        assert_eq!(ordinal(111), "111th");
+        assert_eq!(ordinal(101), "101st");
+    }
+
+    #[test]
+    fn test_zero_fill() {
+        assert_eq!(zero_fill(5, 2, false), "05");
+        assert_eq!(zero_fill(123, 2, false), "123");
+        assert_eq!(zero_fill(-5, 4, false), "-0005");
+        assert_eq!(zero_fill(5, 3, true), "+005");
+    }
+}
@@ -0,0 +1,71 @@
+//! MULTIモードのメモ保存先
+//!
+//! ThinoのMULTIモードと同様に、指定した1つのファイルに日付ヘッダーごとにメモをまとめる。
+//! 日付ヘッダーはデイリーノートの日付フォーマットで生成する。
+
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::obsidian;
+
+/// 日付ヘッダーの接頭辞
+const DATE_HEADING_PREFIX: &str = "## ";
+
+/// MULTIモードのファイルパスを生成する
+///
+/// 拡張子が省略されている場合は `.md` を付与する。
+///
+/// # 引数
+///
+/// * `vault_path` - Obsidian Vaultのパス
+/// * `file` - メモを保存するファイル（Vaultルートからの相対パス）
+pub fn multi_file_path(vault_path: &Path, file: &str) -> PathBuf {
+    let path = vault_path.join(file);
+    if path.extension().is_some() {
+        path
+    } else {
+        path.with_extension("md")
+    }
+}
+
+/// 日付ヘッダーを生成する
+///
+/// # 引数
+///
+/// * `date_format` - Obsidian形式の日付フォーマット（例: "YYYY-MM-DD"）
+/// * `date` - 対象の日付
+///
+/// # 戻り値
+///
+/// "## 2026-01-15" 形式のヘッダー行
+pub fn date_heading(date_format: &str, date: NaiveDate) -> String {
+    format!(
+        "{DATE_HEADING_PREFIX}{}",
+        obsidian::format_date(date_format, date)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_file_path_adds_extension() {
+        let result = multi_file_path(Path::new("/vault"), "Thino");
+        assert_eq!(result, PathBuf::from("/vault/Thino.md"));
+    }
+
+    #[test]
+    fn test_multi_file_path_keeps_extension() {
+        let result = multi_file_path(Path::new("/vault"), "Memos/all.md");
+        assert_eq!(result, PathBuf::from("/vault/Memos/all.md"));
+    }
+
+    #[test]
+    fn test_date_heading() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        assert_eq!(date_heading("YYYY-MM-DD", date), "## 2026-01-15");
+        assert_eq!(date_heading("DD.MM.YYYY", date), "## 15.01.2026");
+    }
+}
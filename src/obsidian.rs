@@ -1,17 +1,22 @@
 //! Obsidian設定ファイル読み取り
 //!
 //! Obsidian Vault内の設定ファイルを読み取り、デイリーノートやThinoプラグインの
-//! 設定を取得する。
+//! 設定を取得する。Obsidian本体の設定（`obsidian.json`）からは登録済みのVaultを取得する。
 
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use chrono::NaiveDate;
-use serde::Deserialize;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::moment;
 
 /// デイリーノートプラグインの設定
-#[allow(dead_code)]
+///
+/// ライブラリからは `DailyNotesSettings::new` で作成する。
 #[derive(Debug, Deserialize)]
+#[non_exhaustive]
 pub struct DailyNotesSettings {
     /// デイリーノートを保存するフォルダ（Vaultルートからの相対パス）
     #[serde(default)]
@@ -20,6 +25,10 @@ pub struct DailyNotesSettings {
     /// 日付フォーマット（例: "YYYY-MM-DD"）
     #[serde(default = "default_format")]
     pub format: String,
+
+    /// 新しいデイリーノートに使うテンプレート（Vaultルートからの相対パス、拡張子は省略可）
+    #[serde(default)]
+    pub template: String,
 }
 
 impl Default for DailyNotesSettings {
@@ -27,12 +36,28 @@ impl Default for DailyNotesSettings {
         Self {
             folder: String::new(),
             format: default_format(),
+            template: String::new(),
+        }
+    }
+}
+
+impl DailyNotesSettings {
+    /// テンプレートを使わない設定を作成する
+    ///
+    /// # 引数
+    ///
+    /// * `folder` - デイリーノートを保存するフォルダ（Vaultルートからの相対パス）
+    /// * `format` - 日付フォーマット（例: "YYYY-MM-DD"）
+    pub fn new(folder: impl Into<String>, format: impl Into<String>) -> Self {
+        Self {
+            folder: folder.into(),
+            format: format.into(),
+            template: String::new(),
         }
     }
 }
 
 /// デフォルトの日付フォーマットを返す
-#[allow(dead_code)]
 fn default_format() -> String {
     "YYYY-MM-DD".to_string()
 }
@@ -49,7 +74,6 @@ fn default_format() -> String {
 /// # 戻り値
 ///
 /// デイリーノート設定。エラー時はデフォルト値。
-#[allow(dead_code)]
 pub fn load_daily_notes_settings(vault_path: &Path) -> DailyNotesSettings {
     let settings_path = vault_path.join(".obsidian").join("daily-notes.json");
 
@@ -59,29 +83,266 @@ pub fn load_daily_notes_settings(vault_path: &Path) -> DailyNotesSettings {
         .unwrap_or_default()
 }
 
-/// 未サポートの日付フォーマットパターンかどうかを判定
-///
-/// ddd（曜日）、MMM（月名）、wo（週番号）などのパターンが含まれる場合はtrueを返す
-#[allow(dead_code)]
-fn has_unsupported_pattern(format: &str) -> bool {
-    // 未サポートパターンのリスト
-    // ddd/dddd: 曜日
-    // MMM/MMMM: 月名
-    // wo: 週番号（序数）
-    // ww/WW/W/w: 週番号
-    // Do: 日（序数）
-    // Mo: 月（序数）
-    // Qo/Q: 四半期
-    let unsupported = [
-        "dddd", "ddd", "MMMM", "MMM", "wo", "ww", "WW", "Do", "Mo", "Qo", "Q", "W", "w",
-    ];
-
-    for pattern in unsupported {
-        if format.contains(pattern) {
-            return true;
+/// Thinoの挿入位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InsertPosition {
+    /// セクション末尾に追記
+    #[default]
+    Append,
+    /// ヘッダー直後に挿入
+    Prepend,
+}
+
+impl<'de> Deserialize<'de> for InsertPosition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // 値の大小文字は区別せず、未知の値は既定値として扱う
+        let value = String::deserialize(deserializer)?;
+        if value.eq_ignore_ascii_case("prepend") {
+            Ok(InsertPosition::Prepend)
+        } else {
+            Ok(InsertPosition::Append)
+        }
+    }
+}
+
+/// Thinoのメモ保存モード
+///
+/// thn設定ファイルでは小文字（`daily` / `file` / `multi` / `canvas`）で指定する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SaveMode {
+    /// デイリーノートに追記
+    #[default]
+    Daily,
+    /// 1メモを1ファイルとして保存
+    File,
+    /// 1つのファイルに日付ヘッダーごとに追記
+    Multi,
+    /// キャンバスファイルにテキストノードとして追加
+    Canvas,
+}
+
+impl SaveMode {
+    /// 設定ファイルでの表記を返す
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SaveMode::Daily => "daily",
+            SaveMode::File => "file",
+            SaveMode::Multi => "multi",
+            SaveMode::Canvas => "canvas",
+        }
+    }
+}
+
+/// Thinoの保存モードを読み取る
+///
+/// Thinoは大文字（"DAILY" 等）で保存するため大小文字を区別せず、未知の値は既定値として扱う。
+fn deserialize_thino_save_mode<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<SaveMode, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    match value.to_ascii_lowercase().as_str() {
+        "file" => Ok(SaveMode::File),
+        "multi" => Ok(SaveMode::Multi),
+        "canvas" => Ok(SaveMode::Canvas),
+        _ => Ok(SaveMode::Daily),
+    }
+}
+
+/// Thino（obsidian-memos）プラグインの設定
+///
+/// キー名はプラグインの `data.json` に合わせてPascalCaseで読み取る。
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ThinoSettings {
+    /// メモを挿入するヘッダー（空の場合はファイル末尾）
+    #[serde(default)]
+    pub insert_after: String,
+
+    /// メモの接頭辞（"List" または "Task"）
+    #[serde(default = "default_prefix")]
+    pub default_prefix: String,
+
+    /// メモ本文の構成（`{TIME}` と `{CONTENT}` を置換）
+    #[serde(default = "default_memo_composition")]
+    pub default_memo_composition: String,
+
+    /// 時刻フォーマット（例: "HH:mm"）
+    #[serde(default = "default_time_format")]
+    pub time_format: String,
+
+    /// ヘッダー配下での挿入位置
+    #[serde(default)]
+    pub insert_position: InsertPosition,
+
+    /// メモの保存モード
+    #[serde(default, deserialize_with = "deserialize_thino_save_mode")]
+    pub save_mode: SaveMode,
+
+    /// FILEモードでメモを保存するフォルダ（Vaultルートからの相対パス）
+    #[serde(default = "default_file_mode_folder")]
+    pub file_mode_folder: String,
+
+    /// MULTIモードでメモを保存するファイル（Vaultルートからの相対パス）
+    #[serde(default = "default_multi_mode_file")]
+    pub multi_mode_file: String,
+
+    /// CANVASモードでメモを保存するキャンバスファイル（Vaultルートからの相対パス）
+    #[serde(default = "default_canvas_mode_file")]
+    pub canvas_mode_file: String,
+}
+
+impl Default for ThinoSettings {
+    fn default() -> Self {
+        Self {
+            insert_after: String::new(),
+            default_prefix: default_prefix(),
+            default_memo_composition: default_memo_composition(),
+            time_format: default_time_format(),
+            insert_position: InsertPosition::default(),
+            save_mode: SaveMode::default(),
+            file_mode_folder: default_file_mode_folder(),
+            multi_mode_file: default_multi_mode_file(),
+            canvas_mode_file: default_canvas_mode_file(),
+        }
+    }
+}
+
+impl ThinoSettings {
+    /// メモ行の接頭辞を返す
+    ///
+    /// `DefaultPrefix` が "Task" の場合はタスク形式（`- [ ] `）、それ以外はリスト形式（`- `）。
+    pub fn line_prefix(&self) -> &'static str {
+        if self.default_prefix == "Task" {
+            "- [ ] "
+        } else {
+            "- "
         }
     }
-    false
+}
+
+/// デフォルトのメモ接頭辞を返す
+fn default_prefix() -> String {
+    "List".to_string()
+}
+
+/// デフォルトのメモ構成を返す
+fn default_memo_composition() -> String {
+    "{TIME} {CONTENT}".to_string()
+}
+
+/// デフォルトの時刻フォーマットを返す
+fn default_time_format() -> String {
+    "HH:mm".to_string()
+}
+
+/// デフォルトのFILEモード保存フォルダを返す
+fn default_file_mode_folder() -> String {
+    "Thino".to_string()
+}
+
+/// デフォルトのMULTIモード保存ファイルを返す
+fn default_multi_mode_file() -> String {
+    "Thino.md".to_string()
+}
+
+/// デフォルトのCANVASモード保存ファイルを返す
+fn default_canvas_mode_file() -> String {
+    "Thino.canvas".to_string()
+}
+
+/// Thinoプラグインの設定を読み込む
+///
+/// `.obsidian/plugins/obsidian-memos/data.json`から設定を読み取る。
+/// ファイルが存在しない場合やパースに失敗した場合はデフォルト値を返す。
+///
+/// # 引数
+///
+/// * `vault_path` - Obsidian Vaultのパス
+///
+/// # 戻り値
+///
+/// Thino設定。エラー時はデフォルト値。
+pub fn load_thino_settings(vault_path: &Path) -> ThinoSettings {
+    let settings_path = vault_path
+        .join(".obsidian")
+        .join("plugins")
+        .join("obsidian-memos")
+        .join("data.json");
+
+    fs::read_to_string(&settings_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Obsidianに登録されたVault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownVault {
+    /// Vaultのパス
+    pub path: PathBuf,
+
+    /// 最後に開いた日時（記録がない場合は `None`）
+    pub last_opened: Option<DateTime<Utc>>,
+}
+
+/// `obsidian.json` の内容
+#[derive(Deserialize)]
+struct ObsidianJson {
+    #[serde(default)]
+    vaults: HashMap<String, VaultEntry>,
+}
+
+/// `obsidian.json` の `vaults` の各要素
+#[derive(Deserialize)]
+struct VaultEntry {
+    path: PathBuf,
+
+    /// 最後に開いた日時（UNIXエポックからのミリ秒）
+    #[serde(default)]
+    ts: Option<i64>,
+}
+
+/// Obsidian本体の設定ファイル（`{config_dir}/obsidian/obsidian.json`）のパスを返す
+///
+/// `dirs::config_dir()` が利用できない環境では `None`。
+pub fn global_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("obsidian").join("obsidian.json"))
+}
+
+/// Obsidianに登録されたVaultを読み込む
+///
+/// ファイルが存在しない場合やパースに失敗した場合は空の一覧を返す。
+///
+/// # 引数
+///
+/// * `path` - Obsidian本体の設定ファイル（`obsidian.json`）のパス
+///
+/// # 戻り値
+///
+/// 最後に開いた日時が新しい順のVault一覧
+pub fn load_known_vaults(path: &Path) -> Vec<KnownVault> {
+    let Some(json) = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<ObsidianJson>(&content).ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut vaults: Vec<KnownVault> = json
+        .vaults
+        .into_values()
+        .map(|entry| KnownVault {
+            path: entry.path,
+            last_opened: entry.ts.and_then(DateTime::from_timestamp_millis),
+        })
+        .collect();
+    vaults.sort_by(|a, b| {
+        b.last_opened
+            .cmp(&a.last_opened)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    vaults
 }
 
 /// Obsidianの日付フォーマットを実際の日付文字列に変換
@@ -100,13 +361,15 @@ fn has_unsupported_pattern(format: &str) -> bool {
 ///
 /// # サポートするパターン
 ///
-/// - `YYYY`: 4桁年（例: 2026）
-/// - `MM`: 2桁月（ゼロ埋め、例: 01）
-/// - `DD`: 2桁日（ゼロ埋め、例: 03）
-///
-/// # フォールバック
+/// moment.jsの日付トークン（英語ロケール）に対応する。
 ///
-/// 未サポートパターン（ddd, MMM等）が含まれる場合は "YYYY-MM-DD" 形式にフォールバック。
+/// - 年: `YYYY`, `YY`, `Y`, `gggg`, `gg`（ロケール週の年）, `GGGG`, `GG`（ISO週の年）
+/// - 月: `MMMM`, `MMM`, `MM`, `Mo`, `M`
+/// - 四半期: `Qo`, `Q`
+/// - 日: `DD`, `Do`, `D`, `DDDD`, `DDDo`, `DDD`（年内通算日）
+/// - 曜日: `dddd`, `ddd`, `dd`, `do`, `d`, `e`, `E`
+/// - 週: `ww`, `wo`, `w`（ロケール週）, `WW`, `Wo`, `W`（ISO週）
+/// - `[...]` で囲んだ文字列と `\` の直後のトークンはそのまま出力する
 ///
 /// # 例
 ///
@@ -119,19 +382,39 @@ fn has_unsupported_pattern(format: &str) -> bool {
 /// assert_eq!(format_date("YYYY/MM/DD", date), "2026/01/03");
 /// assert_eq!(format_date("YYYYMMDD", date), "20260103");
 /// assert_eq!(format_date("DD-MM-YYYY", date), "03-01-2026");
+/// assert_eq!(format_date("YYYY-MM-DD ddd", date), "2026-01-03 Sat");
+/// assert_eq!(format_date("[Week] W, YYYY", date), "Week 1, 2026");
 /// ```
-#[allow(dead_code)]
 pub fn format_date(format: &str, date: NaiveDate) -> String {
-    // 未サポートパターンが含まれる場合はデフォルトフォーマットを使用
-    if has_unsupported_pattern(format) {
-        return date.format("%Y-%m-%d").to_string();
-    }
+    moment::format(format, date)
+}
 
-    // サポートするパターンを置換
-    format
-        .replace("YYYY", &date.format("%Y").to_string())
-        .replace("MM", &date.format("%m").to_string())
-        .replace("DD", &date.format("%d").to_string())
+/// Obsidianの日付フォーマットで書かれた文字列を日付として読み取る
+///
+/// デイリーノートのファイル名から日付を求める際に使う。`format_date` で
+/// 変換し直した結果が元の文字列と一致する場合のみ日付を返す。
+///
+/// # 引数
+///
+/// * `format` - "YYYY-MM-DD" などのフォーマット文字列
+/// * `text` - 読み取る文字列
+///
+/// # 戻り値
+///
+/// 読み取った日付。フォーマットに一致しない場合は `None`。
+///
+/// # 例
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use thn::obsidian::parse_date;
+///
+/// let date = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
+/// assert_eq!(parse_date("YYYY-MM-DD", "2026-01-03"), Some(date));
+/// assert_eq!(parse_date("YYYY-MM-DD", "Meeting notes"), None);
+/// ```
+pub fn parse_date(format: &str, text: &str) -> Option<NaiveDate> {
+    moment::parse(format, text)
 }
 
 #[cfg(test)]
@@ -140,6 +423,61 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
 
+    // ========================================
+    // obsidian.json テスト
+    // ========================================
+
+    #[test]
+    fn test_load_known_vaults_sorted_by_last_opened() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("obsidian.json");
+        fs::write(
+            &path,
+            r#"{
+                "vaults": {
+                    "a1": {"path": "/vaults/old", "ts": 1767225600000},
+                    "b2": {"path": "/vaults/new", "ts": 1768435200000, "open": true},
+                    "c3": {"path": "/vaults/unknown"}
+                },
+                "updateDisabled": false
+            }"#,
+        )
+        .unwrap();
+
+        let vaults = load_known_vaults(&path);
+
+        let paths: Vec<&Path> = vaults.iter().map(|vault| vault.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("/vaults/new"),
+                Path::new("/vaults/old"),
+                Path::new("/vaults/unknown"),
+            ]
+        );
+        assert_eq!(
+            vaults[0].last_opened,
+            DateTime::from_timestamp(1768435200, 0)
+        );
+        assert_eq!(vaults[2].last_opened, None);
+    }
+
+    #[test]
+    fn test_load_known_vaults_missing_or_invalid() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("obsidian.json");
+        assert!(load_known_vaults(&path).is_empty());
+
+        fs::write(&path, "{ invalid json }").unwrap();
+        assert!(load_known_vaults(&path).is_empty());
+    }
+
+    #[test]
+    fn test_global_config_path() {
+        let path = global_config_path().unwrap();
+        assert!(path.ends_with("obsidian/obsidian.json"));
+    }
+
     #[test]
     fn test_daily_notes_settings_default() {
         let settings = DailyNotesSettings::default();
@@ -222,6 +560,150 @@ mod tests {
         assert_eq!(settings.format, "YYYY-MM-DD");
     }
 
+    // ===== ThinoSettings テスト =====
+
+    /// Thinoのdata.jsonを作成するヘルパー関数
+    fn write_thino_data(vault_path: &Path, json: &str) {
+        let plugin_dir = vault_path
+            .join(".obsidian")
+            .join("plugins")
+            .join("obsidian-memos");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(plugin_dir.join("data.json"), json).unwrap();
+    }
+
+    #[test]
+    fn test_thino_settings_default() {
+        let settings = ThinoSettings::default();
+        assert_eq!(settings.insert_after, "");
+        assert_eq!(settings.default_prefix, "List");
+        assert_eq!(settings.default_memo_composition, "{TIME} {CONTENT}");
+        assert_eq!(settings.time_format, "HH:mm");
+        assert_eq!(settings.insert_position, InsertPosition::Append);
+        assert_eq!(settings.save_mode, SaveMode::Daily);
+        assert_eq!(settings.file_mode_folder, "Thino");
+        assert_eq!(settings.multi_mode_file, "Thino.md");
+        assert_eq!(settings.canvas_mode_file, "Thino.canvas");
+    }
+
+    #[test]
+    fn test_load_thino_settings_file_not_found() {
+        let dir = tempdir().unwrap();
+
+        let settings = load_thino_settings(dir.path());
+
+        assert_eq!(settings.insert_after, "");
+        assert_eq!(settings.line_prefix(), "- ");
+    }
+
+    #[test]
+    fn test_load_thino_settings_success() {
+        let dir = tempdir().unwrap();
+        let json = r###"{
+            "InsertAfter": "## Journal",
+            "DefaultPrefix": "Task",
+            "DefaultMemoComposition": "{TIME} | {CONTENT}",
+            "TimeFormat": "HH:mm:ss",
+            "InsertPosition": "prepend",
+            "UserName": "MEMO"
+        }"###;
+        write_thino_data(dir.path(), json);
+
+        let settings = load_thino_settings(dir.path());
+
+        assert_eq!(settings.insert_after, "## Journal");
+        assert_eq!(settings.default_prefix, "Task");
+        assert_eq!(settings.default_memo_composition, "{TIME} | {CONTENT}");
+        assert_eq!(settings.time_format, "HH:mm:ss");
+        assert_eq!(settings.insert_position, InsertPosition::Prepend);
+    }
+
+    #[test]
+    fn test_load_thino_settings_partial_json() {
+        let dir = tempdir().unwrap();
+        write_thino_data(dir.path(), r##"{"InsertAfter": "# Journal"}"##);
+
+        let settings = load_thino_settings(dir.path());
+
+        assert_eq!(settings.insert_after, "# Journal");
+        assert_eq!(settings.default_prefix, "List"); // デフォルト値
+        assert_eq!(settings.time_format, "HH:mm"); // デフォルト値
+    }
+
+    #[test]
+    fn test_load_thino_settings_invalid_json() {
+        let dir = tempdir().unwrap();
+        write_thino_data(dir.path(), "not valid json");
+
+        let settings = load_thino_settings(dir.path());
+
+        // パース失敗時はデフォルト値
+        assert_eq!(settings.insert_after, "");
+    }
+
+    #[test]
+    fn test_insert_position_unknown_value_is_append() {
+        let settings: ThinoSettings = serde_json::from_str(r#"{"InsertPosition": "top"}"#).unwrap();
+        assert_eq!(settings.insert_position, InsertPosition::Append);
+    }
+
+    #[test]
+    fn test_load_thino_settings_file_mode() {
+        let dir = tempdir().unwrap();
+        write_thino_data(
+            dir.path(),
+            r#"{"SaveMode": "FILE", "FileModeFolder": "Memos"}"#,
+        );
+
+        let settings = load_thino_settings(dir.path());
+
+        assert_eq!(settings.save_mode, SaveMode::File);
+        assert_eq!(settings.file_mode_folder, "Memos");
+    }
+
+    #[test]
+    fn test_load_thino_settings_multi_mode() {
+        let dir = tempdir().unwrap();
+        write_thino_data(
+            dir.path(),
+            r#"{"SaveMode": "MULTI", "MultiModeFile": "Memos/all.md"}"#,
+        );
+
+        let settings = load_thino_settings(dir.path());
+
+        assert_eq!(settings.save_mode, SaveMode::Multi);
+        assert_eq!(settings.multi_mode_file, "Memos/all.md");
+    }
+
+    #[test]
+    fn test_load_thino_settings_canvas_mode() {
+        let dir = tempdir().unwrap();
+        write_thino_data(
+            dir.path(),
+            r#"{"SaveMode": "CANVAS", "CanvasModeFile": "Boards/memos.canvas"}"#,
+        );
+
+        let settings = load_thino_settings(dir.path());
+
+        assert_eq!(settings.save_mode, SaveMode::Canvas);
+        assert_eq!(settings.canvas_mode_file, "Boards/memos.canvas");
+    }
+
+    #[test]
+    fn test_thino_save_mode_unknown_value_is_daily() {
+        let settings: ThinoSettings = serde_json::from_str(r#"{"SaveMode": "UNKNOWN"}"#).unwrap();
+        assert_eq!(settings.save_mode, SaveMode::Daily);
+    }
+
+    #[test]
+    fn test_line_prefix() {
+        let mut settings = ThinoSettings::default();
+        assert_eq!(settings.line_prefix(), "- ");
+
+        settings.default_prefix = "Task".to_string();
+        assert_eq!(settings.line_prefix(), "- [ ] ");
+    }
+
     // ===== format_date テスト =====
 
     #[test]
@@ -275,45 +757,48 @@ mod tests {
     }
 
     #[test]
-    fn test_format_date_unsupported_weekday() {
-        // ddd（曜日）が含まれる場合はデフォルトにフォールバック
+    fn test_format_date_weekday() {
+        // ddd（曜日の短縮形）
         let date = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
-        assert_eq!(format_date("YYYY-MM-DD ddd", date), "2026-01-03");
+        assert_eq!(format_date("YYYY-MM-DD ddd", date), "2026-01-03 Sat");
     }
 
     #[test]
-    fn test_format_date_unsupported_full_weekday() {
-        // dddd（完全な曜日名）が含まれる場合はデフォルトにフォールバック
+    fn test_format_date_full_weekday() {
+        // dddd（完全な曜日名）
         let date = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
-        assert_eq!(format_date("dddd, YYYY-MM-DD", date), "2026-01-03");
+        assert_eq!(
+            format_date("dddd, YYYY-MM-DD", date),
+            "Saturday, 2026-01-03"
+        );
     }
 
     #[test]
-    fn test_format_date_unsupported_month_name() {
-        // MMM（月名）が含まれる場合はデフォルトにフォールバック
+    fn test_format_date_month_name() {
+        // MMM（月名の短縮形）
         let date = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
-        assert_eq!(format_date("DD MMM YYYY", date), "2026-01-03");
+        assert_eq!(format_date("DD MMM YYYY", date), "03 Jan 2026");
     }
 
     #[test]
-    fn test_format_date_unsupported_full_month_name() {
-        // MMMM（完全な月名）が含まれる場合はデフォルトにフォールバック
+    fn test_format_date_full_month_name() {
+        // MMMM（完全な月名）
         let date = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
-        assert_eq!(format_date("MMMM DD, YYYY", date), "2026-01-03");
+        assert_eq!(format_date("MMMM DD, YYYY", date), "January 03, 2026");
     }
 
     #[test]
-    fn test_format_date_unsupported_week_number() {
-        // wo（週番号）が含まれる場合はデフォルトにフォールバック
+    fn test_format_date_week_number() {
+        // wo（週番号の序数）
         let date = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
-        assert_eq!(format_date("YYYY-wo", date), "2026-01-03");
+        assert_eq!(format_date("YYYY-wo", date), "2026-1st");
     }
 
     #[test]
-    fn test_format_date_unsupported_ordinal_day() {
-        // Do（序数日）が含まれる場合はデフォルトにフォールバック
+    fn test_format_date_ordinal_day() {
+        // Do（序数日）
         let date = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
-        assert_eq!(format_date("MMMM Do, YYYY", date), "2026-01-03");
+        assert_eq!(format_date("MMMM Do, YYYY", date), "January 3rd, 2026");
     }
 
     #[test]
@@ -325,31 +810,22 @@ mod tests {
 
     #[test]
     fn test_format_date_custom_text() {
-        // フォーマットに任意のテキストを含む場合
+        // フォーマットに任意のテキストを含む場合（moment.jsと同じく [...] でエスケープする）
         let date = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
-        assert_eq!(format_date("note_YYYY-MM-DD", date), "note_2026-01-03");
-    }
-
-    #[test]
-    fn test_has_unsupported_pattern_ddd() {
-        assert!(has_unsupported_pattern("YYYY-MM-DD ddd"));
-    }
-
-    #[test]
-    fn test_has_unsupported_pattern_mmm() {
-        assert!(has_unsupported_pattern("DD MMM YYYY"));
+        assert_eq!(format_date("[note]_YYYY-MM-DD", date), "note_2026-01-03");
     }
 
     #[test]
-    fn test_has_unsupported_pattern_wo() {
-        assert!(has_unsupported_pattern("YYYY-wo"));
+    fn test_format_date_iso_week() {
+        // GGGG/WW（ISO週）は年をまたぐ場合に週の年を使う
+        let date = NaiveDate::from_ymd_opt(2021, 1, 1).unwrap();
+        assert_eq!(format_date("GGGG-[W]WW", date), "2020-W53");
     }
 
     #[test]
-    fn test_has_unsupported_pattern_supported_only() {
-        assert!(!has_unsupported_pattern("YYYY-MM-DD"));
-        assert!(!has_unsupported_pattern("YYYY/MM/DD"));
-        assert!(!has_unsupported_pattern("YYYYMMDD"));
-        assert!(!has_unsupported_pattern("DD-MM-YYYY"));
+    fn test_format_date_escaped_text() {
+        // [...] 内はトークンとして解釈しない
+        let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        assert_eq!(format_date("[Daily] YYYY-[Q]Q", date), "Daily 2026-Q4");
     }
 }
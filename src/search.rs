@@ -0,0 +1,506 @@
+//! メモの全文検索
+//!
+//! デイリーノートフォルダ内のノートをファイル名から日付で絞り込み、
+//! 各ノートのメモを文字列・正規表現・タグで検索する。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use regex::{Regex, RegexBuilder};
+
+use crate::list::{DailyMemo, MemoListing, SkippedNote};
+use crate::memo::MemoError;
+use crate::obsidian::{self, DailyNotesSettings};
+
+/// メモの検索条件
+#[derive(Debug)]
+pub struct SearchQuery {
+    /// 内容の検索パターン（`None` の場合は内容で絞り込まない）
+    pattern: Option<Regex>,
+
+    /// 含むべきタグ（先頭の `#` を除いた小文字）
+    tags: Vec<String>,
+}
+
+impl SearchQuery {
+    /// 検索条件を作成する
+    ///
+    /// # 引数
+    ///
+    /// * `text` - 検索文字列（`None` の場合は内容で絞り込まない）
+    /// * `is_regex` - 検索文字列を正規表現として扱うか
+    /// * `ignore_case` - 大文字小文字を区別しないか
+    /// * `tags` - 含むべきタグ（`#` は省略可、すべて含むメモのみ一致）
+    ///
+    /// # Errors
+    ///
+    /// 正規表現として不正な場合
+    pub fn new(
+        text: Option<&str>,
+        is_regex: bool,
+        ignore_case: bool,
+        tags: &[String],
+    ) -> Result<Self, regex::Error> {
+        let pattern = text
+            .map(|text| {
+                let source = if is_regex {
+                    text.to_string()
+                } else {
+                    regex::escape(text)
+                };
+                RegexBuilder::new(&source)
+                    .case_insensitive(ignore_case)
+                    .build()
+            })
+            .transpose()?;
+
+        let tags = tags
+            .iter()
+            .map(|tag| tag.trim_start_matches('#').to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        Ok(Self { pattern, tags })
+    }
+
+    /// メモの内容が検索条件に一致するかを返す
+    pub fn matches(&self, content: &str) -> bool {
+        if self
+            .pattern
+            .as_ref()
+            .is_some_and(|pattern| !pattern.is_match(content))
+        {
+            return false;
+        }
+
+        let memo_tags = extract_tags(content);
+        self.tags.iter().all(|tag| {
+            memo_tags.iter().any(|memo_tag| {
+                memo_tag == tag
+                    || memo_tag
+                        .strip_prefix(tag.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+        })
+    }
+}
+
+/// デイリーノートのメモを検索する
+///
+/// デイリーノートフォルダ配下（隠しフォルダを除く）の `.md` ファイルのうち、
+/// ファイル名が日付フォーマットに一致するものを対象にする。読み取れないノートや
+/// フォルダは読み飛ばす。メモの時刻はThinoの時刻フォーマットで読み取る。
+///
+/// 読み飛ばしたノートも必要な場合や、thn設定の `time_format` を使う場合は
+/// `search_memos_with_format`（または `Vault::search_memos_with_skipped`）を使う。
+///
+/// # 引数
+///
+/// * `vault_path` - Obsidian Vaultのパス
+/// * `query` - 検索条件
+/// * `from` - 期間の開始日（`None` の場合は制限なし）
+/// * `to` - 期間の終了日（この日を含む、`None` の場合は制限なし）
+///
+/// # 戻り値
+///
+/// 日付順・ノート内の出現順の一致したメモ一覧
+///
+/// # Errors
+///
+/// 読み取れないノートは読み飛ばすため、現在はエラーを返さない
+pub fn search_memos(
+    vault_path: &Path,
+    query: &SearchQuery,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<DailyMemo>, MemoError> {
+    let thino = obsidian::load_thino_settings(vault_path);
+    search_memos_with_format(vault_path, &thino.time_format, query, from, to)
+        .map(|listing| listing.memos)
+}
+
+/// 時刻フォーマットを指定してデイリーノートのメモを検索する
+///
+/// 読み取れないノートやフォルダは読み飛ばし、戻り値の `skipped` に含める。
+///
+/// # 引数
+///
+/// * `vault_path` - Obsidian Vaultのパス
+/// * `time_format` - メモの時刻フォーマット（moment.js形式、例: "HH:mm"）
+/// * `query` - 検索条件
+/// * `from` - 期間の開始日（`None` の場合は制限なし）
+/// * `to` - 期間の終了日（この日を含む、`None` の場合は制限なし）
+///
+/// # Errors
+///
+/// `search_memos` と同じ
+pub fn search_memos_with_format(
+    vault_path: &Path,
+    time_format: &str,
+    query: &SearchQuery,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<MemoListing, MemoError> {
+    let settings = obsidian::load_daily_notes_settings(vault_path);
+    let mut listing = MemoListing::default();
+
+    let mut notes: Vec<(NaiveDate, PathBuf)> =
+        daily_note_files(vault_path, &settings, &mut listing.skipped)
+            .into_iter()
+            .filter(|(date, _)| from.is_none_or(|from| *date >= from))
+            .filter(|(date, _)| to.is_none_or(|to| *date <= to))
+            .collect();
+    notes.sort();
+
+    for (date, path) in notes {
+        listing.read_note(path, date, time_format);
+    }
+    listing.memos.retain(|memo| query.matches(&memo.content));
+
+    Ok(listing)
+}
+
+/// デイリーノートフォルダ内の、ファイル名から日付を読み取れるノートを返す
+///
+/// 読み取れないフォルダは `skipped` に追加する。
+fn daily_note_files(
+    vault_path: &Path,
+    settings: &DailyNotesSettings,
+    skipped: &mut Vec<SkippedNote>,
+) -> Vec<(NaiveDate, PathBuf)> {
+    let root = if settings.folder.is_empty() {
+        vault_path.to_path_buf()
+    } else {
+        vault_path.join(&settings.folder)
+    };
+
+    let mut files = Vec::new();
+    if root.is_dir() {
+        collect_markdown_files(&root, &mut files, skipped);
+    }
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let relative = path.strip_prefix(&root).ok()?.with_extension("");
+            // 日付フォーマットの区切りは `/` なのでOSの区切り文字に依存しない形にする
+            let name = relative
+                .components()
+                .map(|component| component.as_os_str().to_str())
+                .collect::<Option<Vec<_>>>()?
+                .join("/");
+            let date = obsidian::parse_date(&settings.format, &name)?;
+            Some((date, path))
+        })
+        .collect()
+}
+
+/// フォルダ配下の `.md` ファイルを再帰的に集める
+///
+/// `.obsidian` や `.trash` などの隠しフォルダは対象外。読み取れないフォルダは
+/// 読み飛ばして `skipped` に追加し、残りのフォルダの収集を続ける。
+fn collect_markdown_files(dir: &Path, files: &mut Vec<PathBuf>, skipped: &mut Vec<SkippedNote>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            skipped.push(SkippedNote {
+                path: dir.to_path_buf(),
+                error,
+            });
+            return;
+        }
+    };
+
+    for entry in entries {
+        let result = entry.and_then(|entry| Ok((entry.path(), entry.file_type()?)));
+        let (path, file_type) = match result {
+            Ok(entry) => entry,
+            Err(error) => {
+                skipped.push(SkippedNote {
+                    path: dir.to_path_buf(),
+                    error,
+                });
+                continue;
+            }
+        };
+        if path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        {
+            continue;
+        }
+
+        if file_type.is_dir() {
+            collect_markdown_files(&path, files, skipped);
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            files.push(path);
+        }
+    }
+}
+
+/// メモの内容に含まれるタグ（先頭の `#` を除いた小文字）を返す
+///
+/// 行頭または空白の直後の `#` から始まる、英数字・`_`・`-`・`/` の並びをタグとみなす。
+/// 数字のみの並び（`#1` など）はObsidianと同じくタグとして扱わない。
+fn extract_tags(content: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut previous = None;
+
+    for (index, c) in content.char_indices() {
+        if c == '#' && previous.is_none_or(char::is_whitespace) {
+            let tag: String = content[index + 1..]
+                .chars()
+                .take_while(|&c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/'))
+                .collect();
+            if tag.chars().any(|c| !c.is_ascii_digit()) {
+                tags.push(tag.to_lowercase());
+            }
+        }
+        previous = Some(c);
+    }
+
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn query(text: Option<&str>, is_regex: bool, ignore_case: bool, tags: &[&str]) -> SearchQuery {
+        let tags: Vec<String> = tags.iter().map(ToString::to_string).collect();
+        SearchQuery::new(text, is_regex, ignore_case, &tags).unwrap()
+    }
+
+    /// 年/月フォルダにデイリーノートを置いたVaultを作成する
+    fn create_vault(vault_path: &Path) {
+        let obsidian_dir = vault_path.join(".obsidian");
+        fs::create_dir_all(&obsidian_dir).unwrap();
+        fs::write(
+            obsidian_dir.join("daily-notes.json"),
+            r#"{"folder": "Daily", "format": "YYYY/MM/YYYY-MM-DD"}"#,
+        )
+        .unwrap();
+
+        let write = |relative: &str, content: &str| {
+            let path = vault_path.join("Daily").join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            "2025/12/2025-12-31.md",
+            "- 23:00 Year end #review\n- 23:30 party\n",
+        );
+        write(
+            "2026/01/2026-01-02.md",
+            "- 09:00 Meeting with team #work/meeting\n続きの行 deadline\n",
+        );
+        write("2026/01/2026-01-03.md", "- 10:00 meeting notes #Work\n");
+        write(
+            "2026/01/Meeting ideas.md",
+            "- 11:00 Meeting not a daily note\n",
+        );
+        fs::create_dir_all(vault_path.join("Daily").join(".trash")).unwrap();
+        fs::write(
+            vault_path
+                .join("Daily")
+                .join(".trash")
+                .join("2026-01-04.md"),
+            "- 12:00 Meeting deleted\n",
+        )
+        .unwrap();
+    }
+
+    fn summary(memos: &[DailyMemo]) -> Vec<(NaiveDate, &str)> {
+        memos
+            .iter()
+            .map(|memo| (memo.date, memo.time.as_str()))
+            .collect()
+    }
+
+    // ========================================
+    // search_memos テスト
+    // ========================================
+
+    #[test]
+    fn test_search_memos_plain_text() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+
+        let memos = search_memos(
+            dir.path(),
+            &query(Some("Meeting"), false, false, &[]),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(summary(&memos), vec![(date(2026, 1, 2), "09:00")]);
+        assert_eq!(
+            memos[0].path,
+            dir.path().join("Daily/2026/01/2026-01-02.md")
+        );
+    }
+
+    #[test]
+    fn test_search_memos_ignore_case() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+
+        let memos = search_memos(
+            dir.path(),
+            &query(Some("meeting"), false, true, &[]),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            summary(&memos),
+            vec![(date(2026, 1, 2), "09:00"), (date(2026, 1, 3), "10:00")]
+        );
+    }
+
+    #[test]
+    fn test_search_memos_regex_and_continuation() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+
+        let memos = search_memos(
+            dir.path(),
+            &query(Some(r"dead\w+"), true, false, &[]),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(summary(&memos), vec![(date(2026, 1, 2), "09:00")]);
+    }
+
+    #[test]
+    fn test_search_memos_tags() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+
+        // 入れ子のタグも親タグで一致し、大文字小文字は区別しない
+        let memos = search_memos(
+            dir.path(),
+            &query(None, false, false, &["#work"]),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            summary(&memos),
+            vec![(date(2026, 1, 2), "09:00"), (date(2026, 1, 3), "10:00")]
+        );
+    }
+
+    #[test]
+    fn test_search_memos_date_range() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+        let all = query(None, false, false, &[]);
+
+        let memos = search_memos(dir.path(), &all, Some(date(2026, 1, 1)), None).unwrap();
+        assert_eq!(
+            summary(&memos),
+            vec![(date(2026, 1, 2), "09:00"), (date(2026, 1, 3), "10:00")]
+        );
+
+        let memos = search_memos(dir.path(), &all, None, Some(date(2026, 1, 2))).unwrap();
+        assert_eq!(
+            summary(&memos),
+            vec![
+                (date(2025, 12, 31), "23:00"),
+                (date(2025, 12, 31), "23:30"),
+                (date(2026, 1, 2), "09:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_memos_skips_unreadable_notes() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+        // Shift_JISで保存されたノート
+        let note = dir.path().join("Daily").join("2026/01/2026-01-05.md");
+        fs::write(&note, b"- 09:00 Meeting \x83\x81\x83\x82\n").unwrap();
+
+        let listing = search_memos_with_format(
+            dir.path(),
+            "HH:mm",
+            &query(Some("Meeting"), false, false, &[]),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(summary(&listing.memos), vec![(date(2026, 1, 2), "09:00")]);
+        assert_eq!(listing.skipped.len(), 1);
+        assert_eq!(listing.skipped[0].path, note);
+    }
+
+    #[test]
+    fn test_collect_markdown_files_skips_unreadable_folder() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("2026-01-02.md"), "").unwrap();
+        let mut files = Vec::new();
+        let mut skipped = Vec::new();
+
+        collect_markdown_files(dir.path(), &mut files, &mut skipped);
+        collect_markdown_files(&dir.path().join("missing"), &mut files, &mut skipped);
+
+        assert_eq!(files, vec![dir.path().join("2026-01-02.md")]);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, dir.path().join("missing"));
+    }
+
+    #[test]
+    fn test_search_memos_missing_folder() {
+        let dir = tempdir().unwrap();
+
+        let memos =
+            search_memos(dir.path(), &query(Some("a"), false, false, &[]), None, None).unwrap();
+
+        assert!(memos.is_empty());
+    }
+
+    // ========================================
+    // SearchQuery テスト
+    // ========================================
+
+    #[test]
+    fn test_search_query_invalid_regex() {
+        let result = SearchQuery::new(Some("("), true, false, &[]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_search_query_literal_special_chars() {
+        let query = query(Some("c++"), false, false, &[]);
+        assert!(query.matches("learn c++"));
+        assert!(!query.matches("learn c"));
+    }
+
+    #[test]
+    fn test_search_query_requires_all_tags() {
+        let query = query(None, false, false, &["a", "b"]);
+        assert!(query.matches("#a #b"));
+        assert!(!query.matches("#a only"));
+    }
+
+    #[test]
+    fn test_extract_tags() {
+        assert_eq!(
+            extract_tags("#todo item #Project/sub, issue#1 #123 #日本語"),
+            vec!["todo", "project/sub", "日本語"]
+        );
+    }
+}
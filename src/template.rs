@@ -0,0 +1,300 @@
+//! デイリーノートのテンプレート
+//!
+//! デイリーノートプラグインの設定（`daily-notes.json` の `template`）で指定された
+//! テンプレートを読み込み、Obsidianのコアプラグイン「テンプレート」と同様に
+//! `{{date}}`・`{{time}}`・`{{title}}` を展開する。
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+
+use crate::encoding::NoteText;
+use crate::memo::MemoError;
+use crate::moment;
+use crate::obsidian::DailyNotesSettings;
+
+/// `{{time}}` の既定のフォーマット
+const DEFAULT_TIME_FORMAT: &str = "HH:mm";
+
+/// テンプレートファイルのパスを返す
+///
+/// Obsidianと同様に、拡張子が省略されている場合は `.md` を付与する。
+/// テンプレートが設定されていない場合は `None` を返す。
+///
+/// # 引数
+///
+/// * `vault_path` - Obsidian Vaultのパス
+/// * `template` - テンプレートのパス（Vaultルートからの相対パス）
+pub(crate) fn template_path(vault_path: &Path, template: &str) -> Option<PathBuf> {
+    let template = template.trim();
+    if template.is_empty() {
+        return None;
+    }
+
+    let path = vault_path.join(template);
+    if path.extension().is_some() {
+        Some(path)
+    } else {
+        Some(path.with_extension("md"))
+    }
+}
+
+/// 新しいデイリーノートの内容を生成する
+///
+/// テンプレートが設定されていない場合や、テンプレートファイルが存在しない場合は、
+/// Obsidianと同様に空のノートにする。テンプレートの改行コード（CRLF）とBOMは保持する。
+///
+/// # 引数
+///
+/// * `vault_path` - Obsidian Vaultのパス
+/// * `settings` - デイリーノートプラグインの設定
+/// * `datetime` - デイリーノートの日付とメモの記録時刻
+/// * `title` - デイリーノートのファイル名（拡張子を除く）
+///
+/// # Errors
+///
+/// `MemoError::InvalidTemplate` - テンプレートの読み込みに失敗した場合や、UTF-8として
+/// 読めない場合（`io::ErrorKind::InvalidData`）
+pub(crate) fn new_daily_note(
+    vault_path: &Path,
+    settings: &DailyNotesSettings,
+    datetime: NaiveDateTime,
+    title: &str,
+) -> Result<String, MemoError> {
+    let Some(path) = template_path(vault_path, &settings.template) else {
+        return Ok(String::new());
+    };
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(String::new()),
+        Err(err) => return Err(MemoError::InvalidTemplate(path, err)),
+    };
+    let template = match NoteText::decode(&bytes) {
+        Ok(template) => template,
+        Err(err) => {
+            let err = io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("not valid UTF-8: {err}"),
+            );
+            return Err(MemoError::InvalidTemplate(path, err));
+        }
+    };
+
+    Ok(template.encode(&render(
+        &template.content,
+        datetime,
+        &settings.format,
+        title,
+    )))
+}
+
+/// テンプレートの変数を展開する
+///
+/// - `{{date}}` - デイリーノートの日付フォーマットで日付を出力
+/// - `{{time}}` - `HH:mm` で時刻を出力
+/// - `{{title}}` - ノートのタイトルを出力
+/// - `{{date:FORMAT}}`・`{{time:FORMAT}}` - moment.js形式のフォーマットで日時を出力
+///
+/// 変数名の大小文字と前後の空白は区別しない。未知の変数はそのまま残す。
+///
+/// # 引数
+///
+/// * `template` - テンプレートの内容
+/// * `datetime` - 展開する日時
+/// * `date_format` - `{{date}}` に使う日付フォーマット
+/// * `title` - `{{title}}` に使うタイトル
+pub(crate) fn render(
+    template: &str,
+    datetime: NaiveDateTime,
+    date_format: &str,
+    title: &str,
+) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}").map(|end| start + 2 + end) else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+
+        let variable = &rest[start + 2..end];
+        match expand(variable, datetime, date_format, title) {
+            Some(value) => output.push_str(&value),
+            None => output.push_str(&rest[start..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// 変数を展開する（未知の変数の場合は `None`）
+fn expand(
+    variable: &str,
+    datetime: NaiveDateTime,
+    date_format: &str,
+    title: &str,
+) -> Option<String> {
+    let (name, format) = match variable.split_once(':') {
+        Some((name, format)) => (name.trim(), Some(format.trim())),
+        None => (variable.trim(), None),
+    };
+
+    if name.eq_ignore_ascii_case("title") && format.is_none() {
+        return Some(title.to_string());
+    }
+    let default_format = if name.eq_ignore_ascii_case("date") {
+        date_format
+    } else if name.eq_ignore_ascii_case("time") {
+        DEFAULT_TIME_FORMAT
+    } else {
+        return None;
+    };
+
+    let format = format
+        .filter(|format| !format.is_empty())
+        .unwrap_or(default_format);
+    Some(moment::format_datetime(format, datetime))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use tempfile::tempdir;
+
+    fn datetime() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 1, 15)
+            .unwrap()
+            .and_hms_opt(14, 30, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_render_variables() {
+        let template = "# {{title}}\n\nCreated {{date}} {{time}}\n";
+
+        let result = render(template, datetime(), "YYYY-MM-DD", "2026-01-15");
+
+        assert_eq!(result, "# 2026-01-15\n\nCreated 2026-01-15 14:30\n");
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let template = "{{date:dddd, MMMM Do YYYY}} / {{ TIME : h:mm A }} / {{Date}}";
+
+        let result = render(template, datetime(), "DD.MM.YYYY", "15.01.2026");
+
+        assert_eq!(result, "Thursday, January 15th 2026 / 2:30 PM / 15.01.2026");
+    }
+
+    #[test]
+    fn test_render_keeps_unknown_variables() {
+        let template = "{{tomorrow}} {{title:upper}} {{date}} {{unclosed";
+
+        let result = render(template, datetime(), "YYYY-MM-DD", "t");
+
+        assert_eq!(result, "{{tomorrow}} {{title:upper}} 2026-01-15 {{unclosed");
+    }
+
+    #[test]
+    fn test_template_path() {
+        let vault_path = Path::new("/vault");
+
+        assert_eq!(
+            template_path(vault_path, "Templates/Daily"),
+            Some(PathBuf::from("/vault/Templates/Daily.md"))
+        );
+        assert_eq!(
+            template_path(vault_path, "Templates/Daily.md"),
+            Some(PathBuf::from("/vault/Templates/Daily.md"))
+        );
+        assert_eq!(template_path(vault_path, ""), None);
+    }
+
+    #[test]
+    fn test_new_daily_note_from_template() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Templates")).unwrap();
+        fs::write(
+            dir.path().join("Templates").join("Daily.md"),
+            "# {{title}}\n\n## Journal\n",
+        )
+        .unwrap();
+        let settings = DailyNotesSettings {
+            template: "Templates/Daily".to_string(),
+            ..DailyNotesSettings::default()
+        };
+
+        let result = new_daily_note(dir.path(), &settings, datetime(), "2026-01-15").unwrap();
+
+        assert_eq!(result, "# 2026-01-15\n\n## Journal\n");
+    }
+
+    #[test]
+    fn test_new_daily_note_missing_template() {
+        let dir = tempdir().unwrap();
+        let settings = DailyNotesSettings {
+            template: "Templates/Missing".to_string(),
+            ..DailyNotesSettings::default()
+        };
+
+        assert_eq!(
+            new_daily_note(dir.path(), &settings, datetime(), "2026-01-15").unwrap(),
+            ""
+        );
+        assert_eq!(
+            new_daily_note(
+                dir.path(),
+                &DailyNotesSettings::default(),
+                datetime(),
+                "2026-01-15"
+            )
+            .unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_new_daily_note_keeps_crlf_and_bom() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Daily.md"), "\u{feff}# {{title}}\r\n\r\n").unwrap();
+        let settings = DailyNotesSettings {
+            template: "Daily".to_string(),
+            ..DailyNotesSettings::default()
+        };
+
+        assert_eq!(
+            new_daily_note(dir.path(), &settings, datetime(), "2026-01-15").unwrap(),
+            "\u{feff}# 2026-01-15\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn test_new_daily_note_invalid_template() {
+        let dir = tempdir().unwrap();
+        // Shift_JISで保存されたテンプレート
+        fs::write(dir.path().join("Daily.md"), b"# \x83\x81\x83\x82\n").unwrap();
+        // フォルダはファイルとして読み込めない
+        fs::create_dir(dir.path().join("Folder.md")).unwrap();
+
+        for template in ["Daily", "Folder"] {
+            let settings = DailyNotesSettings {
+                template: template.to_string(),
+                ..DailyNotesSettings::default()
+            };
+
+            let result = new_daily_note(dir.path(), &settings, datetime(), "2026-01-15");
+
+            assert!(
+                matches!(&result, Err(MemoError::InvalidTemplate(path, _))
+                    if *path == dir.path().join(format!("{template}.md"))),
+                "{result:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,328 @@
+//! Obsidian Vaultのハンドル
+//!
+//! ライブラリとしてthnを利用する際の入口。Vaultのパスとthn設定をまとめて保持し、
+//! Obsidian設定の読み取りやメモの追記を行う。
+
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::clock::{self, Clock};
+use crate::config::{self, Config, ConfigError};
+use crate::list::{self, DailyMemo, MemoListing};
+use crate::memo::{self, MemoError, MemoWarning};
+use crate::obsidian::{self, DailyNotesSettings, ThinoSettings};
+use crate::search::{self, SearchQuery};
+
+/// Obsidian Vaultのハンドル
+#[derive(Debug)]
+pub struct Vault {
+    config: Config,
+    clock: Box<dyn Clock>,
+}
+
+impl Vault {
+    /// Vaultを開く
+    ///
+    /// thn設定ファイルは読み込まず、Thinoプラグインの設定のみに従う。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - Obsidian Vaultのパス
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::VaultNotFound` - パスが存在しない場合
+    /// - `ConfigError::NotObsidianVault` - `.obsidian` ディレクトリがない場合
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, ConfigError> {
+        Self::from_config(Config::new(path))
+    }
+
+    /// thn設定からVaultを開く
+    ///
+    /// 設定ファイルの `insert_after` や `save_mode` などの上書き設定も適用される。
+    /// `timezone` が設定されていれば、記録日時はそのタイムゾーンで決まる。
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::VaultNotFound` - パスが存在しない場合
+    /// - `ConfigError::NotObsidianVault` - `.obsidian` ディレクトリがない場合
+    pub fn from_config(config: Config) -> Result<Self, ConfigError> {
+        config::validate_vault_path(&config.vault_path)?;
+        let clock = clock::system_clock(config.timezone);
+        Ok(Self { config, clock })
+    }
+
+    /// 現在日時の取得元を差し替える
+    ///
+    /// 既定はthn設定のタイムゾーンのシステム時計。テストなどで記録日時を固定する場合に使う。
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// thn設定ファイル（`{config_dir}/thn/config.toml`）の既定のVaultを開く
+    ///
+    /// `thn` コマンドと同じく、環境変数 `THN_VAULT` が設定されていればその名前付きVaultを開く。
+    /// 名前を指定して開く場合は `config::load_vault` の結果を `from_config` に渡す。
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::NotFound` - 設定ファイルが存在しない場合
+    /// - `ConfigError::NoDefaultVault` - 既定のVaultが設定されていない場合
+    /// - その他、設定の読み込みやVaultの検証に失敗した場合
+    pub fn from_default_config() -> Result<Self, ConfigError> {
+        Self::from_config(config::load()?)
+    }
+
+    /// Vaultのパスを返す
+    pub fn path(&self) -> &Path {
+        &self.config.vault_path
+    }
+
+    /// thn設定を返す
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// 今日の日付を返す
+    ///
+    /// thn設定の `day_start` より前の時刻は前日として扱う。
+    pub fn today(&self) -> NaiveDate {
+        memo::note_date(&self.config, self.clock.now())
+    }
+
+    /// デイリーノートプラグインの設定を読み込む
+    pub fn daily_notes_settings(&self) -> DailyNotesSettings {
+        obsidian::load_daily_notes_settings(self.path())
+    }
+
+    /// Thinoプラグインの設定を読み込む
+    pub fn thino_settings(&self) -> ThinoSettings {
+        obsidian::load_thino_settings(self.path())
+    }
+
+    /// 指定した日付のデイリーノートのパスを返す
+    pub fn daily_note_path(&self, date: NaiveDate) -> PathBuf {
+        memo::daily_note_path(self.path(), &self.daily_notes_settings(), date)
+    }
+
+    /// メモを保存する
+    ///
+    /// 保存先と書式は `thn` コマンドと同じく、Obsidian・Thinoの設定とthn設定に従う。
+    /// 記録日時はVaultの時計から取得する。保存時の警告（競合コピーなど）を返す。
+    ///
+    /// # Errors
+    ///
+    /// `memo::append_memo` と同じ
+    pub fn append_memo(&self, content: &str) -> Result<Vec<MemoWarning>, MemoError> {
+        memo::append_memo(&self.config, content, self.clock.as_ref())
+    }
+
+    /// 記録日時を指定してメモを保存する
+    ///
+    /// # Errors
+    ///
+    /// `memo::append_memo_at` と同じ
+    pub fn append_memo_at(
+        &self,
+        content: &str,
+        timestamp: NaiveDateTime,
+    ) -> Result<Vec<MemoWarning>, MemoError> {
+        memo::append_memo_at(&self.config, content, timestamp)
+    }
+
+    /// 期間内のデイリーノートのメモを返す
+    ///
+    /// メモの時刻は `thn` コマンドと同じく、thn設定またはThinoの時刻フォーマットで読み取る。
+    /// 読み取れないノートは読み飛ばす。読み飛ばしたノートも必要な場合は
+    /// `list_memos_with_skipped` を使う。
+    ///
+    /// # Errors
+    ///
+    /// `list::list_memos` と同じ
+    pub fn list_memos(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyMemo>, MemoError> {
+        self.list_memos_with_skipped(from, to)
+            .map(|listing| listing.memos)
+    }
+
+    /// 期間内のデイリーノートのメモと、読み取れずに読み飛ばしたノートを返す
+    ///
+    /// `thn list` はこの結果の `skipped` を警告として表示する。
+    ///
+    /// # Errors
+    ///
+    /// `list::list_memos` と同じ
+    pub fn list_memos_with_skipped(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<MemoListing, MemoError> {
+        let thino = self.thino_settings();
+        let time_format = memo::time_format(&self.config, &thino);
+        list::list_memos_with_format(self.path(), time_format, from, to)
+    }
+
+    /// デイリーノートのメモを検索する
+    ///
+    /// メモの時刻は `list_memos` と同じく読み取る。読み取れないノートやフォルダは読み飛ばす。
+    /// 読み飛ばしたノートも必要な場合は `search_memos_with_skipped` を使う。
+    ///
+    /// # Errors
+    ///
+    /// `search::search_memos` と同じ
+    pub fn search_memos(
+        &self,
+        query: &SearchQuery,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DailyMemo>, MemoError> {
+        self.search_memos_with_skipped(query, from, to)
+            .map(|listing| listing.memos)
+    }
+
+    /// デイリーノートのメモを検索し、読み取れずに読み飛ばしたノートやフォルダとともに返す
+    ///
+    /// `thn search` はこの結果の `skipped` を警告として表示する。
+    ///
+    /// # Errors
+    ///
+    /// `search::search_memos` と同じ
+    pub fn search_memos_with_skipped(
+        &self,
+        query: &SearchQuery,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<MemoListing, MemoError> {
+        let thino = self.thino_settings();
+        let time_format = memo::time_format(&self.config, &thino);
+        search::search_memos_with_format(self.path(), time_format, query, from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn create_vault(path: &Path) {
+        fs::create_dir_all(path.join(".obsidian")).unwrap();
+    }
+
+    #[test]
+    fn test_open_valid_vault() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+
+        let vault = Vault::open(dir.path()).unwrap();
+
+        assert_eq!(vault.path(), dir.path());
+        assert!(vault.config().save_mode.is_none());
+    }
+
+    #[test]
+    fn test_open_not_obsidian_vault() {
+        let dir = tempdir().unwrap();
+
+        let result = Vault::open(dir.path());
+
+        assert!(matches!(result, Err(ConfigError::NotObsidianVault(_))));
+    }
+
+    #[test]
+    fn test_open_vault_not_found() {
+        let result = Vault::open("/nonexistent/vault/path");
+
+        assert!(matches!(result, Err(ConfigError::VaultNotFound(_))));
+    }
+
+    #[test]
+    fn test_daily_note_path_uses_settings() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+        fs::write(
+            dir.path().join(".obsidian").join("daily-notes.json"),
+            r#"{"folder": "Daily", "format": "DD.MM.YYYY"}"#,
+        )
+        .unwrap();
+
+        let vault = Vault::open(dir.path()).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+
+        assert_eq!(
+            vault.daily_note_path(date),
+            dir.path().join("Daily").join("15.01.2026.md")
+        );
+    }
+
+    #[test]
+    fn test_append_memo() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let vault = Vault::open(dir.path())
+            .unwrap()
+            .with_clock(FixedClock(date.and_hms_opt(8, 5, 0).unwrap()));
+        vault.append_memo("ライブラリからのメモ").unwrap();
+
+        let today = vault.today();
+        assert_eq!(today, date);
+        let content = fs::read_to_string(dir.path().join("2026-01-15.md")).unwrap();
+        assert_eq!(content, "- 08:05 ライブラリからのメモ\n");
+
+        let memos = vault.list_memos(today, today).unwrap();
+        assert_eq!(memos.len(), 1);
+        assert_eq!(memos[0].content, "ライブラリからのメモ");
+    }
+
+    #[test]
+    fn test_list_memos_uses_config_time_format() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+        fs::write(dir.path().join("2026-01-15.md"), "- 9:05 PM 夜\n").unwrap();
+
+        let vault = Vault::from_config(Config {
+            vault_path: dir.path().to_path_buf(),
+            time_format: Some("h:mm A".to_string()),
+            ..Config::default()
+        })
+        .unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+
+        let memos = vault.list_memos(date, date).unwrap();
+        assert_eq!(memos.len(), 1);
+        assert_eq!(memos[0].time, "9:05 PM");
+        assert_eq!(memos[0].content, "夜");
+    }
+
+    #[test]
+    fn test_list_and_search_report_skipped_notes() {
+        let dir = tempdir().unwrap();
+        create_vault(dir.path());
+        fs::write(dir.path().join("2026-01-15.md"), "- 09:00 朝\n").unwrap();
+        // Shift_JISで保存されたノート
+        fs::write(
+            dir.path().join("2026-01-16.md"),
+            b"- 09:00 \x83\x81\x83\x82\n",
+        )
+        .unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+        let from = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let to = NaiveDate::from_ymd_opt(2026, 1, 16).unwrap();
+
+        let listing = vault.list_memos_with_skipped(from, to).unwrap();
+        assert_eq!(listing.memos.len(), 1);
+        assert_eq!(listing.skipped.len(), 1);
+        assert_eq!(listing.skipped[0].path, dir.path().join("2026-01-16.md"));
+
+        let query = SearchQuery::new(Some("朝"), false, false, &[]).unwrap();
+        let listing = vault.search_memos_with_skipped(&query, None, None).unwrap();
+        assert_eq!(listing.memos.len(), 1);
+        assert_eq!(listing.skipped.len(), 1);
+
+        assert_eq!(vault.list_memos(from, to).unwrap().len(), 1);
+    }
+}
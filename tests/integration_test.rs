@@ -24,6 +24,33 @@ fn create_test_vault(vault_path: &std::path::Path) {
         .expect("failed to write daily-notes.json");
 }
 
+/// テストで固定する現在日時（`THN_NOW`）
+const TEST_NOW: &str = "2026-01-15T14:30:00";
+
+/// `TEST_NOW` の日付のデイリーノート
+const TEST_NOTE: &str = "2026-01-15.md";
+
+/// 設定ディレクトリと現在日時を固定したthnコマンドを作成するヘルパー関数
+fn thn_command(config_dir: &std::path::Path) -> Command {
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir)
+        .env("XDG_CACHE_HOME", config_dir)
+        .env("HOME", config_dir)
+        .env("THN_NOW", TEST_NOW)
+        .env_remove("THN_VAULT");
+    cmd
+}
+
+/// Vaultを作成してinitまで実行するヘルパー関数
+fn init_test_vault(vault_dir: &std::path::Path, config_dir: &std::path::Path) {
+    create_test_vault(vault_dir);
+    thn_command(config_dir)
+        .arg("--init")
+        .arg(vault_dir)
+        .assert()
+        .success();
+}
+
 // ========================================
 // テスト1: init -> memo -> config フロー
 // ========================================
@@ -55,6 +82,7 @@ fn test_init_memo_config_flow() {
     let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
     cmd.env("XDG_CONFIG_HOME", config_dir.path())
         .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
         .arg("テストメモ");
 
     cmd.assert().success();
@@ -63,6 +91,7 @@ fn test_init_memo_config_flow() {
     let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
     cmd.env("XDG_CONFIG_HOME", config_dir.path())
         .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
         .arg("--config");
 
     cmd.assert()
@@ -72,8 +101,7 @@ fn test_init_memo_config_flow() {
         .stdout(predicate::str::contains("daily_format:"));
 
     // Step 4: デイリーノートファイルの内容を確認
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let note_path = vault_dir.path().join(format!("{today}.md"));
+    let note_path = vault_dir.path().join(TEST_NOTE);
 
     assert!(note_path.exists(), "デイリーノートが作成されていない");
 
@@ -105,14 +133,14 @@ fn test_multiple_memos() {
         let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
         cmd.env("XDG_CONFIG_HOME", config_dir.path())
             .env("HOME", config_dir.path())
+            .env("THN_NOW", TEST_NOW)
             .arg(format!("メモ{i}"));
 
         cmd.assert().success();
     }
 
     // デイリーノートの内容を確認
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let note_path = vault_dir.path().join(format!("{today}.md"));
+    let note_path = vault_dir.path().join(TEST_NOTE);
 
     let content = fs::read_to_string(&note_path).expect("failed to read daily note");
 
@@ -167,6 +195,7 @@ fn test_error_not_configured() {
     let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
     cmd.env("XDG_CONFIG_HOME", config_dir.path())
         .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
         .arg("テストメモ");
 
     cmd.assert()
@@ -182,6 +211,7 @@ fn test_error_not_configured_config_command() {
     let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
     cmd.env("XDG_CONFIG_HOME", config_dir.path())
         .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
         .arg("--config");
 
     cmd.assert()
@@ -234,6 +264,7 @@ fn test_config_shows_vault_path() {
     let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
     cmd.env("XDG_CONFIG_HOME", config_dir.path())
         .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
         .arg("--config");
 
     // Vaultパスが出力に含まれることを確認
@@ -243,6 +274,57 @@ fn test_config_shows_vault_path() {
         .stdout(predicate::str::contains(vault_path_str.as_ref()));
 }
 
+#[test]
+fn test_memo_written_to_config_daily_note() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    create_test_vault(vault_dir.path());
+    fs::write(
+        vault_dir.path().join(".obsidian").join("daily-notes.json"),
+        r#"{"folder": "Daily", "format": "YYYY-MM-DD dddd"}"#,
+    )
+    .expect("failed to write daily-notes.json");
+
+    let config_dir = tempdir().expect("failed to create temp config directory");
+
+    // init
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .arg("--init")
+        .arg(vault_dir.path());
+
+    cmd.assert().success();
+
+    // config が表示するデイリーノートのパスを取得
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    let output = cmd
+        .env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
+        .arg("--config")
+        .output()
+        .expect("failed to run thn --config");
+    let stdout = String::from_utf8(output.stdout).expect("stdout is not UTF-8");
+    let note_path = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("daily_note: "))
+        .expect("daily_note is not shown");
+
+    // memo
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
+        .arg("曜日付きのメモ");
+
+    cmd.assert().success();
+
+    // config が表示したファイルにメモが書き込まれる
+    assert!(note_path.ends_with("2026-01-15 Thursday.md"));
+    let content = fs::read_to_string(note_path).expect("failed to read daily note");
+    assert!(content.contains("曜日付きのメモ"));
+}
+
 #[test]
 fn test_memo_format_contains_time() {
     let vault_dir = tempdir().expect("failed to create temp vault directory");
@@ -263,27 +345,1066 @@ fn test_memo_format_contains_time() {
     let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
     cmd.env("XDG_CONFIG_HOME", config_dir.path())
         .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
         .arg("時刻テスト");
 
     cmd.assert().success();
 
     // デイリーノートの内容を確認
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let note_path = vault_dir.path().join(format!("{today}.md"));
+    let note_path = vault_dir.path().join(TEST_NOTE);
 
     let content = fs::read_to_string(&note_path).expect("failed to read daily note");
 
-    // "- HH:MM 時刻テスト" 形式になっていることを確認
-    assert!(content.contains("- "), "メモがリスト形式になっていない");
-    assert!(content.contains(":"), "時刻が含まれていない");
-    assert!(content.contains("時刻テスト"), "メモ内容が含まれていない");
+    // THN_NOW の時刻で "- HH:MM 時刻テスト" 形式になっていることを確認
+    assert_eq!(content, "- 14:30 時刻テスト\n");
+}
 
-    // 正規表現でフォーマットを確認
-    let re = regex::Regex::new(r"- \d{2}:\d{2} 時刻テスト").unwrap();
-    assert!(
-        re.is_match(&content),
-        "メモフォーマットが正しくない: {content}"
+// ========================================
+// 標準入力からのメモ
+// ========================================
+
+#[test]
+fn test_memo_from_piped_stdin() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    create_test_vault(vault_dir.path());
+
+    let config_dir = tempdir().expect("failed to create temp config directory");
+
+    // init
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .arg("--init")
+        .arg(vault_dir.path());
+
+    cmd.assert().success();
+
+    // 引数なしでパイプ入力
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
+        .write_stdin("パイプ入力\n");
+
+    cmd.assert().success();
+
+    let note_path = vault_dir.path().join(TEST_NOTE);
+    let content = fs::read_to_string(&note_path).expect("failed to read daily note");
+
+    assert_eq!(content, "- 14:30 パイプ入力\n");
+}
+
+#[test]
+fn test_memo_from_stdin_dash_multiline() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    create_test_vault(vault_dir.path());
+
+    let config_dir = tempdir().expect("failed to create temp config directory");
+
+    // init
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .arg("--init")
+        .arg(vault_dir.path());
+
+    cmd.assert().success();
+
+    // `-` 指定で複数行を入力
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
+        .arg("-")
+        .write_stdin("TODO:\n- Task 1\n- Task 2\n");
+
+    cmd.assert().success();
+
+    let note_path = vault_dir.path().join(TEST_NOTE);
+    let content = fs::read_to_string(&note_path).expect("failed to read daily note");
+
+    // 引用符付きの複数行引数と同じ形で書き込まれる
+    assert_eq!(content, "- 14:30 TODO:\n- Task 1\n- Task 2\n");
+}
+
+#[test]
+fn test_error_empty_stdin() {
+    // 空の標準入力
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.arg("-").write_stdin("\n");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("error: memo content required"));
+}
+
+// ========================================
+// エディタでのメモ作成
+// ========================================
+
+/// テスト用の偽エディタスクリプトを作成するヘルパー関数
+///
+/// 渡されたファイルの先頭に `body` を書き込み、`exit_code` で終了するスクリプトを作成する。
+#[cfg(unix)]
+fn create_fake_editor(dir: &std::path::Path, body: &str, exit_code: i32) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let script_path = dir.join("fake-editor.sh");
+    let script = format!(
+        "#!/bin/sh\n{{ printf '%s\\n' '{body}'; cat \"$1\"; }} > \"$1.new\" && mv \"$1.new\" \"$1\"\nexit {exit_code}\n"
     );
+    fs::write(&script_path, script).expect("failed to write fake editor");
+    fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755))
+        .expect("failed to set permissions");
+    script_path
+}
+
+#[cfg(unix)]
+#[test]
+fn test_edit_memo_with_editor() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    create_test_vault(vault_dir.path());
+
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    let editor = create_fake_editor(config_dir.path(), "エディタメモ", 0);
+
+    // init
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .arg("--init")
+        .arg(vault_dir.path());
+
+    cmd.assert().success();
+
+    // --edit
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
+        .env_remove("VISUAL")
+        .env("EDITOR", &editor)
+        .arg("--edit");
+
+    cmd.assert().success();
+
+    let note_path = vault_dir.path().join(TEST_NOTE);
+    let content = fs::read_to_string(&note_path).expect("failed to read daily note");
+
+    // コメント行は除去される
+    assert_eq!(content, "- 14:30 エディタメモ\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_error_edit_editor_failed() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    create_test_vault(vault_dir.path());
+
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    let editor = create_fake_editor(config_dir.path(), "失敗メモ", 1);
+
+    // init
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .arg("--init")
+        .arg(vault_dir.path());
+
+    cmd.assert().success();
+
+    // エディタが非ゼロで終了した場合は書き込まない
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
+        .env("VISUAL", &editor)
+        .arg("--edit");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("error: editor failed"));
+
+    let note_path = vault_dir.path().join(TEST_NOTE);
+    assert!(!note_path.exists(), "デイリーノートが作成されている");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_error_edit_empty_buffer() {
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    // コメント行のみを追加する（内容は空のまま）
+    let editor = create_fake_editor(config_dir.path(), "# only comment", 0);
+
+    let mut cmd = Command::cargo_bin("thn").expect("failed to find thn binary");
+    cmd.env("XDG_CONFIG_HOME", config_dir.path())
+        .env("HOME", config_dir.path())
+        .env("THN_NOW", TEST_NOW)
+        .env("VISUAL", &editor)
+        .arg("--edit");
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "error: aborting due to empty memo",
+    ));
+}
+
+// ========================================
+// list サブコマンド
+// ========================================
+
+#[test]
+fn test_list_today() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    thn_command(config_dir.path())
+        .arg("一つ目")
+        .assert()
+        .success();
+    thn_command(config_dir.path())
+        .arg("-")
+        .write_stdin("二つ目\n続きの行\n")
+        .assert()
+        .success();
+
+    thn_command(config_dir.path())
+        .arg("list")
+        .assert()
+        .success()
+        .stdout("14:30 一つ目\n14:30 二つ目\n続きの行\n");
+}
+
+#[test]
+fn test_list_date_range_and_json() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    fs::write(vault_dir.path().join("2026-10-16.md"), "- 09:15 会議\n").unwrap();
+    fs::write(
+        vault_dir.path().join("2026-10-17.md"),
+        "# Daily\n- 18:00 振り返り\n",
+    )
+    .unwrap();
+
+    thn_command(config_dir.path())
+        .args(["list", "--from", "2026-10-16", "--to", "2026-10-17"])
+        .assert()
+        .success()
+        .stdout("2026-10-16 09:15 会議\n2026-10-17 18:00 振り返り\n");
+
+    let output = thn_command(config_dir.path())
+        .args(["list", "--date", "2026-10-16", "--json"])
+        .output()
+        .expect("failed to run thn list");
+    assert!(output.status.success());
+    let memos: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(memos.as_array().unwrap().len(), 1);
+    assert_eq!(memos[0]["date"], "2026-10-16");
+    assert_eq!(memos[0]["time"], "09:15");
+    assert_eq!(memos[0]["content"], "会議");
+}
+
+#[test]
+fn test_list_skips_invalid_utf8_note() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    fs::write(vault_dir.path().join("2026-10-16.md"), "- 09:15 会議\n").unwrap();
+    // Shift_JISで保存されたノート
+    fs::write(
+        vault_dir.path().join("2026-10-17.md"),
+        b"- 18:00 \x83\x81\x83\x82\n",
+    )
+    .unwrap();
+
+    thn_command(config_dir.path())
+        .args(["list", "--from", "2026-10-16", "--to", "2026-10-17"])
+        .assert()
+        .success()
+        .stdout("2026-10-16 09:15 会議\n")
+        .stderr(
+            predicate::str::contains("warning: skipped")
+                .and(predicate::str::contains("2026-10-17.md")),
+        );
+}
+
+#[test]
+fn test_error_list_invalid_range() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    thn_command(config_dir.path())
+        .args(["list", "--from", "2026-10-18", "--to", "2026-10-16"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid date range"));
+}
+
+// ========================================
+// search サブコマンド
+// ========================================
+
+#[test]
+fn test_search_memos() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    fs::write(
+        vault_dir.path().join("2026-10-16.md"),
+        "- 09:15 Release planning #work\n- 12:00 lunch\n",
+    )
+    .unwrap();
+    fs::write(
+        vault_dir.path().join("2026-10-17.md"),
+        "- 18:00 release retro #work/retro\n",
+    )
+    .unwrap();
+    fs::write(
+        vault_dir.path().join("Release notes.md"),
+        "- 10:00 release\n",
+    )
+    .unwrap();
+
+    thn_command(config_dir.path())
+        .args(["search", "Release"])
+        .assert()
+        .success()
+        .stdout("2026-10-16.md: 2026-10-16 09:15 Release planning #work\n");
+
+    thn_command(config_dir.path())
+        .args(["search", "-i", "release", "--from", "2026-10-17"])
+        .assert()
+        .success()
+        .stdout("2026-10-17.md: 2026-10-17 18:00 release retro #work/retro\n");
+
+    thn_command(config_dir.path())
+        .args([
+            "search",
+            "--tag",
+            "work",
+            "-r",
+            "^[Rr]elease (planning|retro)",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("09:15").and(predicate::str::contains("18:00")));
+}
+
+#[test]
+fn test_error_search_invalid_regex() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    thn_command(config_dir.path())
+        .args(["search", "--regex", "("])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("error: invalid regex"));
+}
+
+// ========================================
+// 記録日時の指定
+// ========================================
+
+#[test]
+fn test_add_at_time_on_past_date() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+    let note_path = vault_dir.path().join("2026-10-16.md");
+    fs::write(&note_path, "- 08:00 朝\n- 18:00 夜\n").expect("failed to write daily note");
+
+    thn_command(config_dir.path())
+        .args(["--date", "2026-10-16", "--at", "09:15", "朝会"])
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&note_path).expect("failed to read daily note");
+    assert_eq!(content, "- 08:00 朝\n- 09:15 朝会\n- 18:00 夜\n");
+}
+
+#[test]
+fn test_add_relative_date() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    thn_command(config_dir.path())
+        .args(["add", "--date", "yesterday", "--at", "21:00", "昨日のメモ"])
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(vault_dir.path().join("2026-01-14.md"))
+        .expect("failed to read daily note");
+    assert_eq!(content, "- 21:00 昨日のメモ\n");
+}
+
+#[test]
+fn test_error_add_invalid_time() {
+    let config_dir = tempdir().expect("failed to create temp config directory");
+
+    thn_command(config_dir.path())
+        .args(["--at", "25:00", "メモ"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid time: 25:00"));
+}
+
+#[test]
+fn test_error_invalid_now_override() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    thn_command(config_dir.path())
+        .env("THN_NOW", "tomorrow")
+        .arg("メモ")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("error: invalid THN_NOW: tomorrow"));
+}
+
+#[test]
+fn test_day_start_after_midnight() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+    let config_path = config_dir.path().join("thn").join("config.toml");
+    let config = fs::read_to_string(&config_path).expect("failed to read config");
+    fs::write(&config_path, format!("{config}day_start = \"04:00\"\n"))
+        .expect("failed to write config");
+
+    thn_command(config_dir.path())
+        .env("THN_NOW", "2026-01-16T01:30:00")
+        .arg("深夜のメモ")
+        .assert()
+        .success();
+    thn_command(config_dir.path())
+        .env("THN_NOW", "2026-01-16T01:45:00")
+        .args(["--at", "23:50", "寝る前のメモ"])
+        .assert()
+        .success();
+
+    // 時刻は実際の時刻のまま、前日のデイリーノートに書き込まれる。
+    // 1日の始まりより前の時刻は23:59より後として並べる
+    let content =
+        fs::read_to_string(vault_dir.path().join(TEST_NOTE)).expect("failed to read daily note");
+    assert_eq!(content, "- 23:50 寝る前のメモ\n- 01:30 深夜のメモ\n");
+    assert!(!vault_dir.path().join("2026-01-16.md").exists());
+
+    thn_command(config_dir.path())
+        .env("THN_NOW", "2026-01-16T01:50:00")
+        .arg("list")
+        .assert()
+        .success()
+        .stdout("23:50 寝る前のメモ\n01:30 深夜のメモ\n");
+}
+
+// ========================================
+// タイムゾーン
+// ========================================
+
+#[test]
+fn test_timezone_from_config_and_cli() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+    let config_path = config_dir.path().join("thn").join("config.toml");
+    let config = fs::read_to_string(&config_path).expect("failed to read config");
+    fs::write(&config_path, format!("{config}timezone = \"Asia/Tokyo\"\n"))
+        .expect("failed to write config");
+
+    // 2026-01-15 23:30 UTC は東京では 2026-01-16 08:30
+    thn_command(config_dir.path())
+        .env("THN_NOW", "2026-01-15T23:30:00Z")
+        .arg("東京のメモ")
+        .assert()
+        .success();
+    // --tz は設定ファイルより優先される
+    thn_command(config_dir.path())
+        .env("THN_NOW", "2026-01-15T23:30:00Z")
+        .args(["--tz", "UTC", "UTCのメモ"])
+        .assert()
+        .success();
+
+    let tokyo = fs::read_to_string(vault_dir.path().join("2026-01-16.md"))
+        .expect("failed to read daily note");
+    assert_eq!(tokyo, "- 08:30 東京のメモ\n");
+    let utc =
+        fs::read_to_string(vault_dir.path().join(TEST_NOTE)).expect("failed to read daily note");
+    assert_eq!(utc, "- 23:30 UTCのメモ\n");
+
+    thn_command(config_dir.path())
+        .args(["--tz", "UTC", "config"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("timezone: UTC"));
+}
+
+#[test]
+fn test_timezone_dst_transition() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    // ベルリンでは 2026-03-29 02:00 (CET) に 03:00 (CEST) へ進む
+    for (now, memo) in [
+        ("2026-03-29T00:30:00Z", "切り替え前"),
+        ("2026-03-29T01:30:00Z", "切り替え後"),
+    ] {
+        thn_command(config_dir.path())
+            .env("THN_NOW", now)
+            .args(["--tz", "Europe/Berlin", memo])
+            .assert()
+            .success();
+    }
+
+    let content = fs::read_to_string(vault_dir.path().join("2026-03-29.md"))
+        .expect("failed to read daily note");
+    assert_eq!(content, "- 01:30 切り替え前\n- 03:30 切り替え後\n");
+}
+
+#[test]
+fn test_error_unknown_timezone() {
+    let config_dir = tempdir().expect("failed to create temp config directory");
+
+    thn_command(config_dir.path())
+        .args(["--tz", "Mars/Olympus", "メモ"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Mars/Olympus"));
+}
+
+// ========================================
+// 名前付きVault
+// ========================================
+
+#[test]
+fn test_named_vaults() {
+    let personal_dir = tempdir().expect("failed to create temp vault directory");
+    let work_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(personal_dir.path(), config_dir.path());
+    create_test_vault(work_dir.path());
+    thn_command(config_dir.path())
+        .args(["init", "--name", "work"])
+        .arg(work_dir.path())
+        .assert()
+        .success();
+
+    thn_command(config_dir.path())
+        .arg("個人のメモ")
+        .assert()
+        .success();
+    thn_command(config_dir.path())
+        .args(["--vault", "work", "仕事のメモ"])
+        .assert()
+        .success();
+    thn_command(config_dir.path())
+        .env("THN_VAULT", "work")
+        .args(["add", "環境変数のメモ"])
+        .assert()
+        .success();
+
+    assert_eq!(read_today_note(personal_dir.path()), "- 14:30 個人のメモ\n");
+    assert_eq!(
+        read_today_note(work_dir.path()),
+        "- 14:30 仕事のメモ\n- 14:30 環境変数のメモ\n"
+    );
+
+    thn_command(config_dir.path())
+        .args(["list", "--vault", "work"])
+        .assert()
+        .success()
+        .stdout("14:30 仕事のメモ\n14:30 環境変数のメモ\n");
+}
+
+#[test]
+fn test_init_keeps_unparseable_config() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    create_test_vault(vault_dir.path());
+    let config_path = config_dir.path().join("thn").join("config.toml");
+    fs::create_dir_all(config_path.parent().unwrap()).expect("failed to create config dir");
+    let config = r#"default_vault = "work"
+timezone = "Bad/Zone"
+
+[vaults.work]
+vault_path = "/vaults/work"
+
+[vaults.personal]
+vault_path = "/vaults/personal"
+"#;
+    fs::write(&config_path, config).expect("failed to write config");
+
+    // パースできない設定ファイルを上書きしない
+    thn_command(config_dir.path())
+        .arg("init")
+        .arg(vault_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("error: toml parse error"));
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), config);
+}
+
+#[test]
+fn test_init_with_vault_option() {
+    let personal_dir = tempdir().expect("failed to create temp vault directory");
+    let work_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(personal_dir.path(), config_dir.path());
+    create_test_vault(work_dir.path());
+
+    // 旧形式の `--init` でも `--vault` の名前で登録する
+    thn_command(config_dir.path())
+        .args(["--vault", "work", "--init"])
+        .arg(work_dir.path())
+        .assert()
+        .success();
+
+    thn_command(config_dir.path())
+        .args(["config", "--vault", "work"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "vault_path: {}",
+            work_dir.path().display()
+        )));
+    thn_command(config_dir.path())
+        .arg("config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "vault_path: {}",
+            personal_dir.path().display()
+        )));
+
+    thn_command(config_dir.path())
+        .args(["--vault", "work", "init", "--name", "personal"])
+        .arg(work_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "error: conflicting vault names: --name personal and --vault work",
+        ));
+}
+
+#[test]
+fn test_error_vault_without_path() {
+    let work_dir = tempdir().expect("failed to create temp directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    let config_path = config_dir.path().join("thn").join("config.toml");
+    fs::create_dir_all(config_path.parent().unwrap()).expect("failed to create config dir");
+    fs::write(
+        &config_path,
+        "default_vault = \"work\"\n\n[vaults.work]\ninsert_after = \"## Log\"\n",
+    )
+    .expect("failed to write config");
+
+    for args in [&["メモ"][..], &["config"]] {
+        thn_command(config_dir.path())
+            .current_dir(work_dir.path())
+            .args(args)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "error: vault_path is not set for vault: work",
+            ));
+    }
+    assert_eq!(fs::read_dir(work_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_error_unknown_vault() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    thn_command(config_dir.path())
+        .args(["--vault", "work", "メモ"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "error: unknown vault: work. run 'thn init --name work <PATH>' to add it",
+        ));
+}
+
+// ========================================
+// Obsidianに登録されたVaultの選択
+// ========================================
+
+/// Obsidian本体の設定ファイル（`obsidian.json`）を作成するヘルパー関数
+fn write_obsidian_json(config_dir: &std::path::Path, vaults: &[(&std::path::Path, i64)]) {
+    let vaults: serde_json::Map<String, serde_json::Value> = vaults
+        .iter()
+        .enumerate()
+        .map(|(index, (path, ts))| {
+            (
+                format!("vault{index}"),
+                serde_json::json!({"path": path, "ts": ts}),
+            )
+        })
+        .collect();
+    let obsidian_dir = config_dir.join("obsidian");
+    fs::create_dir_all(&obsidian_dir).expect("failed to create obsidian config directory");
+    fs::write(
+        obsidian_dir.join("obsidian.json"),
+        serde_json::json!({ "vaults": vaults }).to_string(),
+    )
+    .expect("failed to write obsidian.json");
+}
+
+#[test]
+fn test_init_selects_known_vault() {
+    let old_dir = tempdir().expect("failed to create temp vault directory");
+    let recent_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    create_test_vault(old_dir.path());
+    create_test_vault(recent_dir.path());
+    write_obsidian_json(
+        config_dir.path(),
+        &[
+            (old_dir.path(), 1767225600000),
+            (recent_dir.path(), 1768435200000),
+        ],
+    );
+
+    // 最後に開いた日時が新しい順に番号が振られる
+    thn_command(config_dir.path())
+        .arg("init")
+        .write_stdin("2\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "  1) {} (last opened ",
+            recent_dir.path().display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "  2) {} (last opened ",
+            old_dir.path().display()
+        )))
+        .stdout(predicate::str::contains("Vault number or path: "));
+
+    thn_command(config_dir.path())
+        .arg("選んだVaultへのメモ")
+        .assert()
+        .success();
+    assert_eq!(
+        read_today_note(old_dir.path()),
+        "- 14:30 選んだVaultへのメモ\n"
+    );
+}
+
+#[test]
+fn test_init_known_vaults_manual_entry() {
+    let known_dir = tempdir().expect("failed to create temp vault directory");
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    create_test_vault(vault_dir.path());
+    write_obsidian_json(config_dir.path(), &[(known_dir.path(), 1768435200000)]);
+
+    thn_command(config_dir.path())
+        .arg("init")
+        .write_stdin(format!("{}\n", vault_dir.path().display()))
+        .assert()
+        .success();
+    thn_command(config_dir.path())
+        .arg("手入力したVaultへのメモ")
+        .assert()
+        .success();
+    assert_eq!(
+        read_today_note(vault_dir.path()),
+        "- 14:30 手入力したVaultへのメモ\n"
+    );
+
+    thn_command(config_dir.path())
+        .arg("init")
+        .write_stdin("5\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("error: invalid vault number: 5"));
+}
+
+#[test]
+fn test_init_known_vaults_use_configured_timezone() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+    write_obsidian_json(config_dir.path(), &[(vault_dir.path(), 1768435200000)]);
+    let config_path = config_dir.path().join("thn").join("config.toml");
+    let config = fs::read_to_string(&config_path).expect("failed to read config");
+    fs::write(&config_path, format!("timezone = \"Asia/Tokyo\"\n{config}"))
+        .expect("failed to write config");
+
+    // 2026-01-15T00:00:00Z は東京では 09:00
+    thn_command(config_dir.path())
+        .arg("init")
+        .write_stdin("1\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(last opened 2026-01-15 09:00)"));
+}
+
+// ========================================
+// 同時実行
+// ========================================
+
+#[test]
+fn test_concurrent_memos_are_not_lost() {
+    const PROCESSES: usize = 32;
+
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    let children: Vec<std::process::Child> = (0..PROCESSES)
+        .map(|i| {
+            std::process::Command::new(assert_cmd::cargo::cargo_bin("thn"))
+                .env("XDG_CONFIG_HOME", config_dir.path())
+                .env("XDG_CACHE_HOME", config_dir.path())
+                .env("HOME", config_dir.path())
+                .env("THN_NOW", TEST_NOW)
+                .env_remove("THN_VAULT")
+                .arg(format!("memo-{i:02}"))
+                .spawn()
+                .expect("failed to spawn thn")
+        })
+        .collect();
+    for mut child in children {
+        assert!(child.wait().expect("failed to wait for thn").success());
+    }
+
+    let content = read_today_note(vault_dir.path());
+    assert_eq!(content.lines().count(), PROCESSES, "note:\n{content}");
+    for i in 0..PROCESSES {
+        let line = format!("- 14:30 memo-{i:02}");
+        assert_eq!(
+            content.lines().filter(|l| *l == line).count(),
+            1,
+            "{line} in note:\n{content}"
+        );
+    }
+    // 一時ファイルやロックファイルがVaultに残っていない
+    let mut names: Vec<String> = fs::read_dir(vault_dir.path())
+        .expect("failed to read vault directory")
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name != ".obsidian")
+        .collect();
+    names.sort();
+    assert_eq!(names, vec![TEST_NOTE]);
+}
+
+// ========================================
+// 時刻フォーマット
+// ========================================
+
+#[test]
+fn test_time_format_twelve_hour() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+    let config_path = config_dir.path().join("thn").join("config.toml");
+    let config = fs::read_to_string(&config_path).expect("failed to read config");
+    fs::write(&config_path, format!("{config}time_format = \"h:mm A\"\n"))
+        .expect("failed to write config");
+
+    thn_command(config_dir.path())
+        .arg("午後のメモ")
+        .assert()
+        .success();
+    thn_command(config_dir.path())
+        .args(["--at", "9:05", "朝のメモ"])
+        .assert()
+        .success();
+
+    let content =
+        fs::read_to_string(vault_dir.path().join(TEST_NOTE)).expect("failed to read daily note");
+    assert_eq!(content, "- 9:05 AM 朝のメモ\n- 2:30 PM 午後のメモ\n");
+
+    thn_command(config_dir.path())
+        .args(["list", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""time": "2:30 PM""#))
+        .stdout(predicate::str::contains(r#""content": "午後のメモ""#));
+    thn_command(config_dir.path())
+        .arg("config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("time_format: h:mm A"));
+}
+
+// ========================================
+// 同期クライアントの競合コピー
+// ========================================
+
+#[test]
+fn test_sync_conflict_warns_by_default() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+    let conflict = vault_dir
+        .path()
+        .join("2026-01-15.sync-conflict-20260115-101500-ABCDEFG.md");
+    fs::write(&conflict, "- 10:15 別端末のメモ\n").expect("failed to write conflict copy");
+
+    thn_command(config_dir.path())
+        .arg("競合中のメモ")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning: sync conflict copies of"))
+        .stderr(predicate::str::contains(
+            "2026-01-15.sync-conflict-20260115-101500-ABCDEFG.md",
+        ));
+
+    let content =
+        fs::read_to_string(vault_dir.path().join(TEST_NOTE)).expect("failed to read daily note");
+    assert_eq!(content, "- 14:30 競合中のメモ\n");
+}
+
+#[test]
+fn test_sync_conflict_refuse() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+    let config_path = config_dir.path().join("thn").join("config.toml");
+    let config = fs::read_to_string(&config_path).expect("failed to read config");
+    fs::write(
+        &config_path,
+        format!("{config}on_sync_conflict = \"refuse\"\n"),
+    )
+    .expect("failed to write config");
+    fs::write(vault_dir.path().join(TEST_NOTE), "- 09:00 朝\n").expect("failed to write note");
+    fs::write(
+        vault_dir.path().join("2026-01-15 (conflict).md"),
+        "- 09:05 別端末\n",
+    )
+    .expect("failed to write conflict copy");
+
+    thn_command(config_dir.path())
+        .arg("競合中のメモ")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("error: sync conflict copies of"))
+        .stderr(predicate::str::contains("2026-01-15 (conflict).md"));
+
+    let content =
+        fs::read_to_string(vault_dir.path().join(TEST_NOTE)).expect("failed to read daily note");
+    assert_eq!(content, "- 09:00 朝\n");
+
+    thn_command(config_dir.path())
+        .arg("config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("on_sync_conflict: refuse"));
+}
+
+// ========================================
+// サブコマンドと既定の add
+// ========================================
+
+/// 今日のデイリーノートの内容を読み込むヘルパー関数
+fn read_today_note(vault_dir: &std::path::Path) -> String {
+    fs::read_to_string(vault_dir.join(TEST_NOTE)).expect("failed to read daily note")
+}
+
+#[test]
+fn test_subcommands_init_config_add() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    create_test_vault(vault_dir.path());
+    let config_dir = tempdir().expect("failed to create temp config directory");
+
+    thn_command(config_dir.path())
+        .arg("init")
+        .arg(vault_dir.path())
+        .assert()
+        .success();
+
+    thn_command(config_dir.path())
+        .arg("config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("vault_path:"));
+
+    thn_command(config_dir.path())
+        .args(["add", "サブコマンドのメモ"])
+        .assert()
+        .success();
+
+    assert!(read_today_note(vault_dir.path()).contains("サブコマンドのメモ"));
+}
+
+#[test]
+fn test_memo_starting_with_subcommand_name() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    // サブコマンド名で始まるメモは add または -- で追記する
+    thn_command(config_dir.path())
+        .args(["add", "list", "groceries"])
+        .assert()
+        .success();
+    thn_command(config_dir.path())
+        .args(["--", "search", "for", "a", "flat"])
+        .assert()
+        .success();
+
+    let content = read_today_note(vault_dir.path());
+    assert!(content.contains("list groceries"));
+    assert!(content.contains("search for a flat"));
+}
+
+#[test]
+fn test_subcommand_name_is_not_a_memo() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    // 先頭がサブコマンド名の場合はサブコマンドとして解釈し、メモは追記しない
+    thn_command(config_dir.path())
+        .args(["list", "groceries"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unexpected argument"));
+
+    assert!(!vault_dir.path().join(TEST_NOTE).exists());
+}
+
+#[test]
+fn test_memo_starting_with_hyphen() {
+    let vault_dir = tempdir().expect("failed to create temp vault directory");
+    let config_dir = tempdir().expect("failed to create temp config directory");
+    init_test_vault(vault_dir.path(), config_dir.path());
+
+    thn_command(config_dir.path())
+        .args(["-5", "degrees", "--outside"])
+        .assert()
+        .success();
+    thn_command(config_dir.path())
+        .args(["add", "--", "--edit", "is", "a", "flag"])
+        .assert()
+        .success();
+
+    let content = read_today_note(vault_dir.path());
+    assert!(content.contains("-5 degrees --outside"));
+    assert!(content.contains("--edit is a flag"));
+}
+
+#[test]
+fn test_legacy_flags_conflict_with_subcommands() {
+    let config_dir = tempdir().expect("failed to create temp config directory");
+
+    thn_command(config_dir.path())
+        .args(["--config", "list"])
+        .assert()
+        .failure();
 }
 
 // ========================================